@@ -1,9 +1,5 @@
 use crate::*;
-use eframe::{
-    egui::{self, RichText, Visuals},
-    epaint::Color32,
-    epi,
-};
+use eframe::{egui, epi};
 #[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
 #[cfg_attr(feature = "persistence", serde(default))]
 pub struct AppDorothy {
@@ -12,6 +8,48 @@ pub struct AppDorothy {
     pub pbhl_honors: PBHLHonors,
     pub selected_raid: Raid,
     pub config: AppSettings,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub session_start: String,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub export_window_open: bool,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub import_status: Option<String>,
+    /// Why the last export failed.
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub export_status: Option<String>,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub tracker_import: import::TrackerImport,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub share_stats: share::ShareStats,
+    /// Drop Totals from a share link the webapp was opened with, shown instead of the log.
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub shared_totals: Option<AppSettings>,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub pull_inputs: pulls::PullInputs,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub odds_simulation: gacha::OddsSimulation,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub roll_entry: pulls::RollEntry,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub hotkey_editor: hotkeys::HotkeyEditor,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub theme_editor: theme::ThemeEditor,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub highlight_editor: highlight::HighlightEditor,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub layout_editor: layout::LayoutEditor,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub overlay_window: overlay::OverlayWindow,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub updater: updater::Updater,
+    #[cfg(target_arch = "wasm32")]
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    /// The link Share Link... made, or why it couldn't make one.
+    pub share_link: Option<Result<String, String>>,
+    #[cfg(target_arch = "wasm32")]
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub pending_import: export::PendingImport,
 }
 
 impl Default for AppDorothy {
@@ -22,6 +60,123 @@ impl Default for AppDorothy {
             pbhl_honors: PBHLHonors::Ignore,
             selected_raid: Raid::None,
             config: AppSettings::default(),
+            session_start: get_time(),
+            export_window_open: false,
+            import_status: None,
+            export_status: None,
+            tracker_import: import::TrackerImport::default(),
+            share_stats: share::ShareStats::default(),
+            pull_inputs: pulls::PullInputs::default(),
+            odds_simulation: gacha::OddsSimulation::default(),
+            roll_entry: pulls::RollEntry::default(),
+            hotkey_editor: hotkeys::HotkeyEditor::default(),
+            theme_editor: theme::ThemeEditor::default(),
+            highlight_editor: highlight::HighlightEditor::default(),
+            layout_editor: layout::LayoutEditor::default(),
+            overlay_window: overlay::OverlayWindow::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            updater: updater::Updater::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            shared_totals: None,
+            #[cfg(target_arch = "wasm32")]
+            shared_totals: share::shared_totals_from_location()
+                .map(|totals| totals.to_settings(&AppSettings::default())),
+            #[cfg(target_arch = "wasm32")]
+            share_link: None,
+            #[cfg(target_arch = "wasm32")]
+            pending_import: Default::default(),
+        }
+    }
+}
+
+impl AppDorothy {
+    fn run_hotkey_action(&mut self, action: hotkeys::HotkeyAction) {
+        use hotkeys::HotkeyAction;
+        let settings = &mut self.config.app_settings;
+        match action {
+            HotkeyAction::ToggleRightPanel => {
+                settings.right_panel_visible = !settings.right_panel_visible
+            }
+            HotkeyAction::ToggleLeftPanel => {
+                settings.left_panel_visible = !settings.left_panel_visible
+            }
+            HotkeyAction::ToggleAlwaysOnTop => settings.always_on_top = !settings.always_on_top,
+            HotkeyAction::ToggleDarkMode => {
+                settings.dark_mode = !settings.dark_mode;
+                settings.theme = None;
+            }
+            HotkeyAction::Export => self.export_drops(),
+            HotkeyAction::ToggleCenterPanelFeatures => {
+                settings.toggle_active_items = !settings.toggle_active_items
+            }
+            HotkeyAction::LogNoDrop => self.log_tab_item(Item::NoDrop),
+            HotkeyAction::LogItem(item) => self.log_tab_item(item),
+            HotkeyAction::Undo => self.config.undo_last_drop(),
+            HotkeyAction::ToggleCompactOverlay => {
+                settings.overlay.enabled = !settings.overlay.enabled
+            }
+            HotkeyAction::LogKill => self.config.log_kill(&self.pbhl_honors),
+        }
+    }
+
+    /// Exports the whole log, then resets it if Reset Counts on Export is ticked. A failed export
+    /// keeps the log and says why.
+    fn export_drops(&mut self) {
+        let settings = &self.config.app_settings;
+        match export(
+            self.config.droplog.clone(),
+            settings.export_format,
+            &settings.highlight_rules,
+        ) {
+            Ok(()) => {
+                if settings.reset_on_export {
+                    self.config.reset_drops();
+                }
+            }
+            Err(e) => self.export_failed("the drop log", e),
+        }
+    }
+
+    fn export_failed(&mut self, contents: &str, error: Box<dyn std::error::Error>) {
+        self.export_status = Some(format!("Dorothy couldn't export {}: {}", contents, error));
+    }
+
+    /// Runs the actions of pressed hotkeys and logs the items of pressed Quick Entry Mode keys,
+    /// unless a text field or the hotkey editor is taking the keyboard.
+    fn process_hotkeys(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() || self.hotkey_editor.is_recording(ctx.input().time) {
+            return;
+        }
+        let actions = self.config.app_settings.hotkeys.pressed(&ctx.input());
+        for action in actions {
+            self.run_hotkey_action(action);
+        }
+
+        if self.config.app_settings.quick_entry {
+            let buttons =
+                hotkeys::quick_entry_buttons(&self.config.app_settings, self.selected_raid);
+            for ((item, raid, chest), key) in buttons {
+                if !ctx.input().key_pressed(key) {
+                    continue;
+                }
+                let modifiers = ctx.input().modifiers;
+                if modifiers.shift_only() {
+                    self.config.remove_last_drop(item, raid, chest);
+                } else if modifiers.is_none() {
+                    self.config.click_item(item, raid, chest, &self.pbhl_honors);
+                }
+            }
+        }
+    }
+
+    /// Logs `item` as if its button on the current tab was clicked, if the tab shows it.
+    fn log_tab_item(&mut self, item: Item) {
+        if let Some((item, raid, chest)) =
+            tab_buttons(&self.config.app_settings, self.selected_raid)
+                .into_iter()
+                .find(|(tab_item, _, _)| *tab_item == item)
+        {
+            self.config.click_item(item, raid, chest, &self.pbhl_honors);
         }
     }
 }
@@ -43,28 +198,14 @@ impl epi::App for AppDorothy {
         if let Some(storage) = _storage {
             *self = epi::get_value(storage, epi::APP_KEY).unwrap_or_default()
         }
+        self.config.app_settings.migrate_item_layouts();
 
-        if self.config.app_settings.auto_update_enabled
+        #[cfg(not(target_arch = "wasm32"))]
         {
-            #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))] 
-                let need_to_update = check_for_update().unwrap();
-            #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
-            match need_to_update {
-                ReleaseStatus::NewVersion => {
-                    let update_worked = self_update();
-                    match update_worked {
-                        Ok(()) => {
-                            self.config.app_settings.auto_update_status = 1;
-                        }
-                        Err(e) => {
-                            println!("{}", e);
-                            self.config.app_settings.auto_update_status = 2;
-                        }
-                    }
-                }
-                ReleaseStatus::UpToDate => {
-                    self.config.app_settings.auto_update_status = 3;
-                }
+            updater::clean_up();
+            if self.config.app_settings.auto_update_enabled {
+                self.updater
+                    .check(_ctx, &self.config.app_settings.update_source, false);
             }
         }
     }
@@ -79,6 +220,10 @@ impl epi::App for AppDorothy {
         std::time::Duration::from_secs(3)
     }
 
+    fn clear_color(&self) -> egui::Rgba {
+        overlay::clear_color(&self.config.app_settings.overlay)
+    }
+
     #[allow(unused_variables)]
     fn update(&mut self, ctx: &egui::Context, frame: &epi::Frame) {
         let Self {
@@ -86,9 +231,15 @@ impl epi::App for AppDorothy {
             config,
             droplog,
             pbhl_honors: pbhlhonors,
-            selected_raid: selectedraid
+            selected_raid: selectedraid,
+            ..
         } = self;
 
+        // Drops loaded from a save haven't been indexed yet.
+        self.config.update_stats();
+        let theme = self.config.app_settings.active_theme();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.theme_editor.apply_font(&theme, ctx);
         if !ctx.is_using_pointer() {
             ctx.set_pixels_per_point(self.config.app_settings.ui_scale);
             let mut style = (*ctx.style()).clone();
@@ -99,35 +250,35 @@ impl epi::App for AppDorothy {
                     crate::app::egui::TextStyle::Heading,
                     crate::app::egui::FontId::new(
                         header_font_size,
-                        crate::app::egui::FontFamily::Proportional,
+                        theme.font_style.family(),
                     ),
                 ),
                 (
                     crate::app::egui::TextStyle::Body,
                     crate::app::egui::FontId::new(
                         self.config.app_settings.body_font_size,
-                        crate::app::egui::FontFamily::Proportional,
+                        theme.font_style.family(),
                     ),
                 ),
                 (
                     crate::app::egui::TextStyle::Monospace,
                     crate::app::egui::FontId::new(
                         self.config.app_settings.body_font_size,
-                        crate::app::egui::FontFamily::Proportional,
+                        theme.font_style.family(),
                     ),
                 ),
                 (
                     crate::app::egui::TextStyle::Button,
                     crate::app::egui::FontId::new(
                         self.config.app_settings.body_font_size,
-                        crate::app::egui::FontFamily::Proportional,
+                        theme.font_style.family(),
                     ),
                 ),
                 (
                     crate::app::egui::TextStyle::Small,
                     crate::app::egui::FontId::new(
                         small_font_size,
-                        crate::app::egui::FontFamily::Proportional,
+                        theme.font_style.family(),
                     ),
                 ),
             ]
@@ -136,50 +287,295 @@ impl epi::App for AppDorothy {
         }
 
         #[cfg(not(target_arch = "wasm32"))]
-        if self.config.app_settings.auto_update_enabled
+        if self.updater.should_show() {
+            let mut updater_open = true;
+            egui::Window::new("About Dorothy")
+                .open(&mut updater_open)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    updater::place_about(
+                        &mut self.updater,
+                        &self.config.app_settings.update_source,
+                        ui,
+                    )
+                });
+            self.updater.open &= updater_open;
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if let Some((file_name, contents)) = self.pending_import.lock().unwrap().take() {
+            self.import_status = Some(
+                match export::import_from_bytes(&contents, ExportFormat::from_file_name(&file_name)) {
+                    Ok(drops) => format!(
+                        "Imported {} drops from {}.",
+                        self.config.import_drops(drops),
+                        file_name
+                    ),
+                    Err(e) => format!("Dorothy couldn't read {}: {}", file_name, e),
+                },
+            );
+        }
+
+        #[cfg(target_arch = "wasm32")]
         {
-            if self.config.app_settings.auto_update_status == 1 {
-                egui::Window::new("Updated!").show(ctx, |ui| {
-                    ui.heading("Please Restart!");
-                    ui.add_space(5.);
-                    ui.label("Dorothy has automatically updated to a new release. A restart is required. Please check the Github repo for what has changed.");
+            let picked_file = self.tracker_import.pending.lock().unwrap().take();
+            if let Some((file_name, contents)) = picked_file {
+                self.tracker_import.load(file_name, contents);
+            }
+        }
+
+        if self.tracker_import.open {
+            let mut tracker_import_open = true;
+            let mut imported_drops = None;
+            egui::Window::new("Import From Another Tracker")
+                .open(&mut tracker_import_open)
+                .vscroll(true)
+                .show(ctx, |ui| {
+                    imported_drops = import::place_import_mapping(
+                        &mut self.tracker_import,
+                        &mut self.config.app_settings.import_presets,
+                        ui,
+                    );
                 });
-            } else if self.config.app_settings.auto_update_status == 2 {
-                egui::Window::new("Update Failed...").show(ctx, |ui| {
-                    ui.heading("404: Dorothy's Brain Not Found");
-                    ui.add_space(5.);
-                    ui.label("Dorothy has failed to update. Please open an issue on Github for Nadya to investigate.");
+            if let Some(drops) = imported_drops {
+                self.import_status = Some(format!(
+                    "Imported {} drops from {}.",
+                    self.config.import_drops(drops),
+                    self.tracker_import.file_name
+                ));
+                tracker_import_open = false;
+            }
+            self.tracker_import.open = tracker_import_open;
+        }
+
+        if self.hotkey_editor.open {
+            let mut hotkey_editor_open = true;
+            egui::Window::new("Hotkeys")
+                .open(&mut hotkey_editor_open)
+                .vscroll(true)
+                .show(ctx, |ui| {
+                    hotkeys::place_hotkey_editor(
+                        &mut self.hotkey_editor,
+                        &mut self.config.app_settings.hotkeys,
+                        ui,
+                    );
                 });
+            self.hotkey_editor.open = hotkey_editor_open;
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let picked_file = self.theme_editor.pending.lock().unwrap().take();
+            if let Some((file_name, contents)) = picked_file {
+                self.theme_editor
+                    .import(&mut self.config.app_settings, &file_name, &contents);
             }
         }
 
-        if self.config.app_settings.dark_mode {
-            ctx.set_visuals(Visuals::dark());
-            self.config.app_settings.dark_mode = true;
-        } else {
-            ctx.set_visuals(Visuals::light());
-            self.config.app_settings.dark_mode = false;
+        if self.theme_editor.open {
+            let mut theme_editor_open = true;
+            egui::Window::new("Themes")
+                .open(&mut theme_editor_open)
+                .show(ctx, |ui| {
+                    theme::place_theme_editor(
+                        &mut self.theme_editor,
+                        &mut self.config.app_settings,
+                        ui,
+                    );
+                });
+            self.theme_editor.open = theme_editor_open;
+        }
+
+        if self.highlight_editor.open {
+            let mut highlight_editor_open = true;
+            egui::Window::new("Highlight Rules")
+                .open(&mut highlight_editor_open)
+                .vscroll(true)
+                .show(ctx, |ui| {
+                    highlight::place_highlight_editor(
+                        &mut self.config.app_settings.highlight_rules,
+                        &theme,
+                        ui,
+                    );
+                });
+            self.highlight_editor.open = highlight_editor_open;
         }
+
+        if self.layout_editor.open {
+            let mut layout_editor_open = true;
+            egui::Window::new("Item Layout")
+                .open(&mut layout_editor_open)
+                .vscroll(true)
+                .show(ctx, |ui| {
+                    layout::place_layout_editor(
+                        &mut self.layout_editor,
+                        &mut self.config.app_settings,
+                        ui,
+                    );
+                });
+            self.layout_editor.open = layout_editor_open;
+        }
+
+        if self.share_stats.open {
+            let mut share_stats_open = true;
+            egui::Window::new("Share Stats")
+                .open(&mut share_stats_open)
+                .show(ctx, |ui| {
+                    share::place_share_stats(&mut self.share_stats, &self.config.droplog.drop, ui);
+                });
+            self.share_stats.open = share_stats_open;
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if let Some(share_link) = self.share_link.clone() {
+            let mut share_link_open = true;
+            egui::Window::new("Share Link")
+                .open(&mut share_link_open)
+                .show(ctx, |ui| match &share_link {
+                    Ok(share_link) => {
+                        ui.label("Anyone opening this link sees your Drop Totals. Your log itself isn't shared.");
+                        ui.horizontal(|ui| {
+                            let mut link = share_link.clone();
+                            ui.text_edit_singleline(&mut link);
+                            if ui.button("Copy").clicked() {
+                                ui.output().copied_text = share_link.clone();
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        ui.label(e);
+                    }
+                });
+            if !share_link_open {
+                self.share_link = None;
+            }
+        }
+
+        if let Some(export_status) = self.export_status.clone() {
+            let mut export_window_open = true;
+            egui::Window::new("Export")
+                .open(&mut export_window_open)
+                .show(ctx, |ui| {
+                    ui.label(export_status);
+                });
+            if !export_window_open {
+                self.export_status = None;
+            }
+        }
+
+        if let Some(import_status) = self.import_status.clone() {
+            let mut import_window_open = true;
+            egui::Window::new("Import")
+                .open(&mut import_window_open)
+                .show(ctx, |ui| {
+                    ui.label(import_status);
+                });
+            if !import_window_open {
+                self.import_status = None;
+            }
+        }
+
+        ctx.set_visuals(theme.visuals());
         
-        if self.config.app_settings.always_on_top {
+        if self.config.app_settings.always_on_top || self.config.app_settings.overlay.enabled {
             frame.set_always_on_top(true)
         } else {
             frame.set_always_on_top(false)
         }
 
+        if let Some(shared_totals) = &self.shared_totals {
+            let mut back_to_log = false;
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.heading("Shared Drop Totals");
+                ui.label("These totals were shared with you and are read-only. Nothing here is added to your log.");
+                if ui.button("Back to My Log").clicked() {
+                    back_to_log = true;
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        place_drop_totals(shared_totals, ui);
+                    });
+            });
+            if back_to_log {
+                self.shared_totals = None;
+                #[cfg(target_arch = "wasm32")]
+                share::clear_shared_totals_location();
+            }
+            return;
+        }
+
+        let overlay_buttons = tab_buttons(&self.config.app_settings, self.selected_raid).len();
+        self.overlay_window.sync(
+            &mut self.config.app_settings.overlay,
+            overlay_buttons,
+            self.config.app_settings.log_by_kill,
+            ctx.input().screen_rect().size(),
+            frame,
+        );
+        if self.config.app_settings.overlay.enabled {
+            let mut close_overlay = false;
+            egui::CentralPanel::default()
+                .frame(overlay::overlay_frame(
+                    &ctx.style(),
+                    self.config.app_settings.overlay.opacity,
+                ))
+                .show(ctx, |ui| {
+                    close_overlay = overlay::place_overlay(
+                        &mut self.config,
+                        self.selected_raid,
+                        &self.pbhl_honors,
+                        frame,
+                        ui,
+                    );
+                });
+            self.process_hotkeys(ctx);
+            if close_overlay {
+                self.config.app_settings.overlay.enabled = false;
+            }
+            return;
+        }
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
                 ui.menu_button("File", |ui| {
-                    #[cfg(not(target_arch = "wasm32"))]
                     if ui.button("Export").clicked() {
-                        let _ = export(self.config.droplog.clone());
-                        if self.config.app_settings.reset_on_export {
-                            self.config.droplog.drop = DropLog::reset();
+                        self.export_drops();
+                    }
+                    if ui.button("Export Roll Log").clicked() {
+                        if let Err(e) = export::export_rolls(
+                            &self.config.roll_log,
+                            self.config.app_settings.export_format,
+                        ) {
+                            self.export_failed("the roll log", e);
                         }
                     }
+                    if ui.button("Filtered Export...").clicked() {
+                        self.export_window_open = true;
+                    }
+                    #[cfg(target_arch = "wasm32")]
+                    if ui.button("Import").clicked() {
+                        let _ = export::pick_import_file(self.pending_import.clone(), ".csv,.json");
+                    }
+                    if ui.button("Import From Another Tracker...").clicked() {
+                        self.tracker_import.open = true;
+                    }
+                    ui.separator();
+                    if ui.button("Share Stats...").clicked() {
+                        self.share_stats.open = true;
+                        self.share_stats.raid =
+                            tab_raid(&self.config.app_settings.current_ui_tab, self.selected_raid);
+                    }
+                    #[cfg(target_arch = "wasm32")]
+                    if ui.button("Share Link...").clicked() {
+                        self.share_link = Some(share::share_link(&share::SharedTotals::from_drops(
+                            &self.config.droplog.drop,
+                        )));
+                    }
                     ui.separator();
                     if ui.button("Reset Droplog").clicked() {
-                        self.config.droplog.drop = DropLog::reset();
+                        self.config.reset_drops();
                     }
                     #[cfg(not(target_arch = "wasm32"))]
                     if ui.button("Quit & Save").clicked() {
@@ -200,6 +596,10 @@ impl epi::App for AppDorothy {
                         &mut self.config.app_settings.move_right_to_bottom,
                         "Move Right Panel to Bottom",
                     );
+                    ui.checkbox(
+                        &mut self.config.app_settings.recent_drops_grid,
+                        "Recent Drops as Grid",
+                    );
                     ui.checkbox(
                         &mut self.config.app_settings.toggle_active_items,
                         "Adjust Center Panel Features",
@@ -208,26 +608,99 @@ impl epi::App for AppDorothy {
                         &mut self.config.app_settings.show_all_drops,
                         "Show All Drop Totals",
                     );
+                    ui.checkbox(&mut self.config.app_settings.quick_entry, "Quick Entry Mode");
+                    if ui
+                        .checkbox(&mut self.config.app_settings.log_by_kill, "Log by Kill")
+                        .changed()
+                    {
+                        self.config.kill.clear();
+                    }
+                    ui.checkbox(
+                        &mut self.config.app_settings.overlay.enabled,
+                        "Compact Overlay",
+                    );
+                    #[cfg(not(target_arch = "wasm32"))]
+                    ui.add(
+                        egui::Slider::new(&mut self.config.app_settings.overlay.opacity, 0.2..=1.)
+                            .text("Overlay Opacity"),
+                    );
                 });
                 ui.menu_button("Settings", |ui| {
                     ui.style_mut().wrap = Some(false);
                     #[cfg(not(target_arch = "wasm32"))]
                     ui.checkbox(
                         &mut self.config.app_settings.auto_update_enabled,
-                        "Auto Update on Startup",
+                        "Check for Updates on Startup",
                     );
-                    ui.checkbox(&mut self.config.app_settings.dark_mode, "Dark Mode");
                     #[cfg(not(target_arch = "wasm32"))]
-                    ui.checkbox(&mut self.config.app_settings.always_on_top, "Always On Top");
+                    if ui.button("Check for Updates...").clicked() {
+                        self.updater
+                            .check(ctx, &self.config.app_settings.update_source, true);
+                        ui.close_menu();
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    ui.menu_button("Update Source", |ui| {
+                        updater::place_update_settings(
+                            &mut self.config.app_settings.update_source,
+                            ui,
+                        )
+                    });
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui
+                        .add_enabled(
+                            updater::can_roll_back(),
+                            egui::Button::new("Roll Back Last Update"),
+                        )
+                        .clicked()
+                    {
+                        self.updater.roll_back();
+                        ui.close_menu();
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button("About Dorothy...").clicked() {
+                        self.updater.open = true;
+                        ui.close_menu();
+                    }
+                    if ui
+                        .checkbox(&mut self.config.app_settings.dark_mode, "Dark Mode")
+                        .changed()
+                    {
+                        self.config.app_settings.theme = None;
+                    }
+                    if ui.button("Themes...").clicked() {
+                        self.theme_editor.open = true;
+                        ui.close_menu();
+                    }
+                    if ui.button("Highlight Rules...").clicked() {
+                        self.highlight_editor.open = true;
+                        ui.close_menu();
+                    }
                     #[cfg(not(target_arch = "wasm32"))]
+                    ui.checkbox(&mut self.config.app_settings.always_on_top, "Always On Top");
                     ui.checkbox(
                         &mut self.config.app_settings.reset_on_export,
                         "Reset Counts on Export",
                     );
+                    ui.horizontal(|ui| {
+                        ui.label("Export as");
+                        ui.radio_value(
+                            &mut self.config.app_settings.export_format,
+                            ExportFormat::Csv,
+                            ".csv",
+                        );
+                        ui.radio_value(
+                            &mut self.config.app_settings.export_format,
+                            ExportFormat::Json,
+                            ".json",
+                        );
+                    });
                     ui.checkbox(
                         &mut self.config.app_settings.droprate_by_kills,
                         "Calculate droprates by total kills",
                     );
+                    if ui.button("Hotkeys...").clicked() {
+                        self.hotkey_editor.open = true;
+                    }
                 });
                 ui.menu_button("Helpful Links", |ui| {
                     ui.style_mut().wrap = Some(false);
@@ -263,485 +736,7 @@ impl epi::App for AppDorothy {
                         .max_height(INFINITY)
                         .max_width(INFINITY)
                         .show(ui, |ui| {
-                            if self.config.app_settings.current_ui_tab == UiTab::Akasha
-                                || self.config.app_settings.show_all_drops
-                            {
-                                place_total_header(
-                                    Raid::Akasha,
-                                    Item::NoDrop,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::NoDrop,
-                                    ChestType::None,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::HollowKey,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::SilverCentrum,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::GoldBrick,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::CoronationRing,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::LineageRing,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::IntricacyRing,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::ChampionMerit,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::SupremeMerit,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::LegendaryMerit,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::WeaponPlusMark1,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::WeaponPlusMark2,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Akasha,
-                                    Item::WeaponPlusMark3,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                            }
-                            if self.config.app_settings.current_ui_tab == UiTab::PBHL
-                                || self.config.app_settings.show_all_drops
-                            {
-                                place_total_header(
-                                    Raid::PBHL,
-                                    Item::NoDrop,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::PBHL,
-                                    Item::NoDrop,
-                                    ChestType::None,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::PBHL,
-                                    Item::GoldBrick,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::PBHL,
-                                    Item::CoronationRing,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::PBHL,
-                                    Item::LineageRing,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::PBHL,
-                                    Item::IntricacyRing,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                            }
-                            if self.config.app_settings.current_ui_tab == UiTab::GOHL
-                                || self.config.app_settings.show_all_drops
-                            {
-                                place_total_header(
-                                    Raid::GOHL,
-                                    Item::NoDrop,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::GOHL,
-                                    Item::NoDrop,
-                                    ChestType::None,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::GOHL,
-                                    Item::VerdantAzurite,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::GOHL,
-                                    Item::SilverCentrum,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::GOHL,
-                                    Item::GoldBrick,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::GOHL,
-                                    Item::CoronationRing,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::GOHL,
-                                    Item::LineageRing,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::GOHL,
-                                    Item::IntricacyRing,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::GOHL,
-                                    Item::ChampionMerit,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::GOHL,
-                                    Item::SupremeMerit,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::GOHL,
-                                    Item::LegendaryMerit,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                            }
-
-                            if self.config.app_settings.current_ui_tab == UiTab::Hosts
-                                || self.config.app_settings.show_all_drops
-                            {
-                                place_total_header(
-                                    Raid::UBHL,
-                                    Item::NoDrop,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::UBHL,
-                                    Item::GoldBrick,
-                                    ChestType::Host,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::UBHL,
-                                    Item::GoldBrick,
-                                    ChestType::Flip,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::PBHL,
-                                    Item::GoldBrick,
-                                    ChestType::Host,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Xeno,
-                                    Item::GoldBrick,
-                                    ChestType::Flip,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Huanglong,
-                                    Item::GoldBrick,
-                                    ChestType::Host,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Qilin,
-                                    Item::GoldBrick,
-                                    ChestType::Host,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::HLQL,
-                                    Item::GoldBrick,
-                                    ChestType::Host,
-                                    &self.config,
-                                    ui,
-                                );
-                            }
-
-                            if self.config.app_settings.current_ui_tab == UiTab::SixDragons
-                                || self.config.app_settings.show_all_drops
-                            {
-                                place_total_header(
-                                    Raid::Wilnas,
-                                    Item::NoDrop,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Wilnas,
-                                    Item::NoDrop,
-                                    ChestType::None,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Wilnas,
-                                    Item::BrimstoneEarrings,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Wilnas,
-                                    Item::EternitySand,
-                                    ChestType::Gold,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_total_header(
-                                    Raid::Wamdus,
-                                    Item::NoDrop,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Wamdus,
-                                    Item::NoDrop,
-                                    ChestType::None,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Wamdus,
-                                    Item::PermafrostEarrings,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Wamdus,
-                                    Item::EternitySand,
-                                    ChestType::Gold,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_total_header(
-                                    Raid::Galleon,
-                                    Item::NoDrop,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Galleon,
-                                    Item::NoDrop,
-                                    ChestType::None,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Galleon,
-                                    Item::BrickearthEarrings,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Galleon,
-                                    Item::EternitySand,
-                                    ChestType::Gold,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_total_header(
-                                    Raid::Ewiyar,
-                                    Item::NoDrop,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Ewiyar,
-                                    Item::NoDrop,
-                                    ChestType::None,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Ewiyar,
-                                    Item::JetstreamEarrings,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Ewiyar,
-                                    Item::EternitySand,
-                                    ChestType::Gold,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_total_header(
-                                    Raid::LuWoh,
-                                    Item::NoDrop,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::LuWoh,
-                                    Item::NoDrop,
-                                    ChestType::None,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::LuWoh,
-                                    Item::SunbeamEarrings,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::LuWoh,
-                                    Item::EternitySand,
-                                    ChestType::Gold,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_total_header(
-                                    Raid::Fediel,
-                                    Item::NoDrop,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Fediel,
-                                    Item::NoDrop,
-                                    ChestType::None,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Fediel,
-                                    Item::NightshadeEarrings,
-                                    ChestType::Blue,
-                                    &self.config,
-                                    ui,
-                                );
-                                place_percentage_label(
-                                    Raid::Fediel,
-                                    Item::EternitySand,
-                                    ChestType::Gold,
-                                    &self.config,
-                                    ui,
-                                );
-                                
-                            }
-                            if self.config.app_settings.current_ui_tab == UiTab::EternitySand
-                                || self.config.app_settings.show_all_drops
-                            {
-
-                                let total_drops_of_item = &self.config
-                                    .droplog
-                                    .drop
-                                    .iter()
-                                    .filter(|x| x.item == Item::EternitySand)
-                                    .count();
-                                
-                                ui.add_space(20.);
-                                ui.heading("Eternity Sands (All Raids) - ".to_owned() + &total_drops_of_item.to_string());
-                                ui.add_space(5.);
-                            }
+                            place_drop_totals(&self.config, ui);
                         });
                     ui.add_space(50.);
                     egui::warn_if_debug_build(ui);
@@ -758,149 +753,7 @@ impl epi::App for AppDorothy {
                     ui.heading("Recent Drops");
                     ui.add_space(5.);
 
-                    egui::ScrollArea::vertical()
-                        .auto_shrink([false, false])
-                        .max_height(INFINITY)
-                        .max_width(INFINITY)
-                        .show(ui, |ui| {
-                            for drop in self
-                                .config
-                                .droplog
-                                .drop
-                                .clone()
-                                .into_iter()
-                                .filter(|x| x.item != Item::NoDrop)
-                                .rev()
-                            {
-                                ui.horizontal(|ui| {
-                                    ui.spacing_mut().item_spacing.x = 0.0;
-                                    if drop.chest == ChestType::Host
-                                        || drop.chest == ChestType::Flip
-                                            && drop.item == Item::GoldBrick
-                                            && drop.raid != Raid::Xeno
-                                    {
-                                        let mut gold_brick_text_color =
-                                            Color32::from_rgb(255, 221, 26);
-                                        if !self.config.app_settings.dark_mode {
-                                            gold_brick_text_color = Color32::from_rgb(187, 152, 10);
-                                        }
-                                        if ui
-                                            .add(
-                                                egui::Label::new(
-                                                    RichText::new(format!(
-                                                        "{} - {}",
-                                                        drop.item, drop.raid
-                                                    ))
-                                                    .color(gold_brick_text_color),
-                                                )
-                                                .sense(egui::Sense::click()),
-                                            )
-                                            .on_hover_text(format!(
-                                                "On {} from {} in a {}",
-                                                drop.date_obtained, drop.raid, drop.chest
-                                            ))
-                                            .clicked()
-                                        {
-                                            let _ = self
-                                                .config
-                                                .droplog
-                                                .drop
-                                                .retain(|x| x.drop_id != drop.drop_id);
-                                        }
-                                        ui.add_space(3.)
-                                    } else if drop.item == Item::GoldBrick
-                                        && drop.raid == Raid::PBHL
-                                        && drop.chest == ChestType::Blue
-                                    {
-                                        let mut gold_brick_text_color =
-                                            Color32::from_rgb(255, 221, 26);
-                                        if !self.config.app_settings.dark_mode {
-                                            gold_brick_text_color = Color32::from_rgb(183, 138, 15);
-                                        }
-                                        if ui
-                                            .add(
-                                                egui::Label::new(
-                                                    RichText::new(format!(
-                                                        "{} - {}",
-                                                        drop.item, drop.raid
-                                                    ))
-                                                    .color(gold_brick_text_color),
-                                                )
-                                                .sense(egui::Sense::click()),
-                                            )
-                                            .on_hover_text(format!(
-                                                "On {} from {} {}",
-                                                drop.date_obtained,
-                                                drop.raid,
-                                                drop.honors.as_ref().unwrap()
-                                            ))
-                                            .clicked()
-                                        {
-                                            let _ = self
-                                                .config
-                                                .droplog
-                                                .drop
-                                                .retain(|x| x.drop_id != drop.drop_id);
-                                        }
-                                        ui.add_space(3.)
-                                    } else if drop.item == Item::GoldBrick {
-                                        let mut gold_brick_text_color =
-                                            Color32::from_rgb(255, 221, 26);
-                                        if !self.config.app_settings.dark_mode {
-                                            gold_brick_text_color = Color32::from_rgb(187, 152, 10);
-                                        }
-                                        if ui
-                                            .add(
-                                                egui::Label::new(
-                                                    RichText::new(format!(
-                                                        "{} - {}",
-                                                        drop.item, drop.raid
-                                                    ))
-                                                    .color(gold_brick_text_color),
-                                                )
-                                                .sense(egui::Sense::click()),
-                                            )
-                                            .on_hover_text(format!(
-                                                "On {} from {}",
-                                                drop.date_obtained, drop.raid
-                                            ))
-                                            .clicked()
-                                        {
-                                            let _ = self
-                                                .config
-                                                .droplog
-                                                .drop
-                                                .retain(|x| x.drop_id != drop.drop_id);
-                                        }
-                                        ui.add_space(3.)
-                                    } else {
-                                        let mut drop_honors = drop.honors.as_ref().unwrap();
-                                        let empty_string = "".to_string();
-                                        if drop.raid != Raid::PBHL {
-                                            drop_honors = &empty_string;
-                                        }
-                                        if ui
-                                            .add(
-                                                egui::Label::new(format!("{}", drop.item))
-                                                    .sense(egui::Sense::click()),
-                                            )
-                                            .on_hover_text(format!(
-                                                "On {} from {} {}",
-                                                drop.date_obtained, drop.raid, drop_honors
-                                            ))
-                                            .clicked()
-                                        {
-                                            let _ = self
-                                                .config
-                                                .droplog
-                                                .drop
-                                                .retain(|x| x.drop_id != drop.drop_id);
-                                        }
-                                        ui.add_space(3.)
-                                    }
-                                });
-                            }
-                        });
+                    recent::place_recent_drops(&mut self.config, &theme, ui);
                 });
         }
 
@@ -917,149 +770,7 @@ impl epi::App for AppDorothy {
                     ui.heading("Recent Drops");
                     ui.add_space(5.);
 
-                    egui::ScrollArea::vertical()
-                        .auto_shrink([false, false])
-                        .max_height(INFINITY)
-                        .max_width(INFINITY)
-                        .show(ui, |ui| {
-                            for drop in self
-                                .config
-                                .droplog
-                                .drop
-                                .clone()
-                                .into_iter()
-                                .filter(|x| x.item != Item::NoDrop)
-                                .rev()
-                            {
-                                ui.horizontal(|ui| {
-                                    ui.spacing_mut().item_spacing.x = 0.0;
-                                    if drop.chest == ChestType::Host
-                                        || drop.chest == ChestType::Flip
-                                            && drop.item == Item::GoldBrick
-                                            && drop.raid != Raid::Xeno
-                                    {
-                                        let mut gold_brick_text_color =
-                                            Color32::from_rgb(255, 221, 26);
-                                        if !self.config.app_settings.dark_mode {
-                                            gold_brick_text_color = Color32::from_rgb(187, 152, 10);
-                                        }
-                                        if ui
-                                            .add(
-                                                egui::Label::new(
-                                                    RichText::new(format!(
-                                                        "{} - {}",
-                                                        drop.item, drop.raid
-                                                    ))
-                                                    .color(gold_brick_text_color),
-                                                )
-                                                .sense(egui::Sense::click()),
-                                            )
-                                            .on_hover_text(format!(
-                                                "On {} from {} in a {}",
-                                                drop.date_obtained, drop.raid, drop.chest
-                                            ))
-                                            .clicked()
-                                        {
-                                            let _ = self
-                                                .config
-                                                .droplog
-                                                .drop
-                                                .retain(|x| x.drop_id != drop.drop_id);
-                                        }
-                                        ui.add_space(3.)
-                                    } else if drop.item == Item::GoldBrick
-                                        && drop.raid == Raid::PBHL
-                                        && drop.chest == ChestType::Blue
-                                    {
-                                        let mut gold_brick_text_color =
-                                            Color32::from_rgb(255, 221, 26);
-                                        if !self.config.app_settings.dark_mode {
-                                            gold_brick_text_color = Color32::from_rgb(183, 138, 15);
-                                        }
-                                        if ui
-                                            .add(
-                                                egui::Label::new(
-                                                    RichText::new(format!(
-                                                        "{} - {}",
-                                                        drop.item, drop.raid
-                                                    ))
-                                                    .color(gold_brick_text_color),
-                                                )
-                                                .sense(egui::Sense::click()),
-                                            )
-                                            .on_hover_text(format!(
-                                                "On {} from {} {}",
-                                                drop.date_obtained,
-                                                drop.raid,
-                                                drop.honors.as_ref().unwrap()
-                                            ))
-                                            .clicked()
-                                        {
-                                            let _ = self
-                                                .config
-                                                .droplog
-                                                .drop
-                                                .retain(|x| x.drop_id != drop.drop_id);
-                                        }
-                                        ui.add_space(3.)
-                                    } else if drop.item == Item::GoldBrick {
-                                        let mut gold_brick_text_color =
-                                            Color32::from_rgb(255, 221, 26);
-                                        if !self.config.app_settings.dark_mode {
-                                            gold_brick_text_color = Color32::from_rgb(187, 152, 10);
-                                        }
-                                        if ui
-                                            .add(
-                                                egui::Label::new(
-                                                    RichText::new(format!(
-                                                        "{} - {}",
-                                                        drop.item, drop.raid
-                                                    ))
-                                                    .color(gold_brick_text_color),
-                                                )
-                                                .sense(egui::Sense::click()),
-                                            )
-                                            .on_hover_text(format!(
-                                                "On {} from {}",
-                                                drop.date_obtained, drop.raid
-                                            ))
-                                            .clicked()
-                                        {
-                                            let _ = self
-                                                .config
-                                                .droplog
-                                                .drop
-                                                .retain(|x| x.drop_id != drop.drop_id);
-                                        }
-                                        ui.add_space(3.)
-                                    } else {
-                                        let mut drop_honors = drop.honors.as_ref().unwrap();
-                                        let empty_string = "".to_string();
-                                        if drop.raid != Raid::PBHL {
-                                            drop_honors = &empty_string;
-                                        }
-                                        if ui
-                                            .add(
-                                                egui::Label::new(format!("{}", drop.item))
-                                                    .sense(egui::Sense::click()),
-                                            )
-                                            .on_hover_text(format!(
-                                                "On {} from {} {}",
-                                                drop.date_obtained, drop.raid, drop_honors
-                                            ))
-                                            .clicked()
-                                        {
-                                            let _ = self
-                                                .config
-                                                .droplog
-                                                .drop
-                                                .retain(|x| x.drop_id != drop.drop_id);
-                                        }
-                                        ui.add_space(3.)
-                                    }
-                                });
-                            }
-                        });
+                    recent::place_recent_drops(&mut self.config, &theme, ui);
                 });
         }
 
@@ -1144,6 +855,12 @@ impl epi::App for AppDorothy {
                 }
             });
 
+            if self.config.app_settings.log_by_kill
+                && self.config.app_settings.current_ui_tab != UiTab::Pulls
+            {
+                ui.add_space(10.);
+                place_kill_record(&mut self.config, &self.pbhl_honors, ui);
+            }
             ui.add_space(30.);
             egui::ScrollArea::both()
                 .auto_shrink([false, false])
@@ -1151,286 +868,67 @@ impl epi::App for AppDorothy {
                 .max_width(INFINITY)
                 .show(ui, |ui| {
                     if self.config.app_settings.current_ui_tab == UiTab::Pulls {
-                        ui.horizontal(|ui| {
-                            ui.label("Crystals: ");
-                            let response = ui.add(egui::TextEdit::singleline(
-                                &mut self.config.app_settings.crystals_amount,
-                            ));
-                            if response.changed() {
-                                self.config.app_settings.total_pulls = calculate_pulls(
-                                    self.config
-                                        .app_settings
-                                        .crystals_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .ten_pulls_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .single_pulls_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .shrimp_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                )
-                            }
-                        });
+                        let settings = &mut self.config.app_settings;
+                        pulls::place_amount_input(
+                            "Crystals: ",
+                            &mut settings.crystals_amount,
+                            &mut self.pull_inputs.crystals,
+                            ui,
+                        );
                         ui.add_space(5.);
-                        ui.horizontal(|ui| {
-                            ui.label("10-Pull Tickets: ");
-                            let response = ui.add(egui::TextEdit::singleline(
-                                &mut self.config.app_settings.ten_pulls_amount,
-                            ));
-                            if response.changed() {
-                                self.config.app_settings.total_pulls = calculate_pulls(
-                                    self.config
-                                        .app_settings
-                                        .crystals_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .ten_pulls_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .single_pulls_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .shrimp_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                )
-                            }
-                        });
+                        pulls::place_amount_input(
+                            "10-Pull Tickets: ",
+                            &mut settings.ten_pulls_amount,
+                            &mut self.pull_inputs.ten_pull_tickets,
+                            ui,
+                        );
                         ui.add_space(5.);
-                        ui.horizontal(|ui| {
-                            ui.label("1-Pull Tickets: ");
-                            let response = ui.add(egui::TextEdit::singleline(
-                                &mut self.config.app_settings.single_pulls_amount,
-                            ));
-                            if response.changed() {
-                                self.config.app_settings.total_pulls = calculate_pulls(
-                                    self.config
-                                        .app_settings
-                                        .crystals_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .ten_pulls_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .single_pulls_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .shrimp_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                )
-                            }
-                        });
+                        pulls::place_amount_input(
+                            "1-Pull Tickets: ",
+                            &mut settings.single_pulls_amount,
+                            &mut self.pull_inputs.single_tickets,
+                            ui,
+                        );
                         ui.add_space(5.);
-                        ui.horizontal(|ui| {
-                            ui.label("Ebi  Fry: ");
-                            let response = ui.add(egui::TextEdit::singleline(
-                                &mut self.config.app_settings.shrimp_amount,
-                            ));
-                            if response.changed() {
-                                self.config.app_settings.total_pulls = calculate_pulls(
-                                    self.config
-                                        .app_settings
-                                        .crystals_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .ten_pulls_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .single_pulls_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                    self.config
-                                        .app_settings
-                                        .shrimp_amount
-                                        .parse::<f32>()
-                                        .unwrap_or_default(),
-                                )
-                            }
-                        });
+                        pulls::place_amount_input(
+                            "Ebi  Fry: ",
+                            &mut settings.shrimp_amount,
+                            &mut self.pull_inputs.shrimps,
+                            ui,
+                        );
                         ui.add_space(5.);
-                        ui.label(&self.config.app_settings.total_pulls);
-                    }
-                    if self.config.app_settings.current_ui_tab == UiTab::Akasha {
-                        egui::Grid::new("akasha_item_grid")
-                            .spacing((
-                                self.config.app_settings.grid_spacing_x,
-                                self.config.app_settings.grid_spacing_y,
-                            ))
-                            .show(ui, |ui| {
-                                ui.style_mut().wrap = Some(false);
-                                if !self.config.app_settings.vertical_grid {
-                                    let akasha_drops = vec![
-                                        Item::NoDrop,
-                                        Item::HollowKey,
-                                        Item::SilverCentrum,
-                                        Item::GoldBrick,
-                                        Item::CoronationRing,
-                                        Item::ChampionMerit,
-                                        Item::WeaponPlusMark1,
-                                        Item::LineageRing,
-                                        Item::SupremeMerit,
-                                        Item::WeaponPlusMark2,
-                                        Item::IntricacyRing,
-                                        Item::LegendaryMerit,
-                                        Item::WeaponPlusMark3,
-                                    ];
-                                    for (pos, drop) in akasha_drops.iter().enumerate() {
-                                        let chest = match drop {
-                                            Item::NoDrop => ChestType::None,
-                                            _ => ChestType::Blue,
-                                        };
-                                        if self.config.app_settings.active_items[pos] {
-                                            ui.horizontal(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Akasha,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 3 || pos == 6 || pos == 9 {
-                                            ui.end_row();
-                                        }
-                                    }
-                                } else {
-                                    let akasha_drops = vec![
-                                        Item::NoDrop,
-                                        Item::HollowKey,
-                                        Item::SilverCentrum,
-                                        Item::GoldBrick,
-                                        Item::CoronationRing,
-                                        Item::ChampionMerit,
-                                        Item::WeaponPlusMark1,
-                                        Item::LineageRing,
-                                        Item::SupremeMerit,
-                                        Item::WeaponPlusMark2,
-                                        Item::IntricacyRing,
-                                        Item::LegendaryMerit,
-                                        Item::WeaponPlusMark3,
-                                    ];
-                                    for (pos, drop) in akasha_drops.iter().enumerate() {
-                                        let chest = match drop {
-                                            Item::NoDrop => ChestType::None,
-                                            _ => ChestType::Blue,
-                                        };
-                                        if self.config.app_settings.active_items[pos] {
-                                            ui.vertical(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Akasha,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 3 || pos == 6 || pos == 9 {
-                                            ui.end_row();
-                                        }
-                                    }
-                                }
-                            });
+                        let breakdown = calculate_pulls(
+                            settings.crystals_amount,
+                            settings.ten_pulls_amount,
+                            settings.single_pulls_amount,
+                            settings.shrimp_amount,
+                        );
+                        pulls::place_pull_breakdown(&breakdown, ui);
+                        ui.add_space(20.);
+                        pulls::place_spark_planner(
+                            &mut settings.spark_plan,
+                            settings.crystals_amount,
+                            breakdown.ticket_pulls.saturating_add(breakdown.shrimp_pulls),
+                            pulls::today(),
+                            ui,
+                        );
+                        ui.add_space(20.);
+                        gacha::place_odds(
+                            &mut settings.odds_settings,
+                            &mut self.odds_simulation,
+                            breakdown.total(),
+                            ui,
+                        );
+                        ui.add_space(20.);
+                        pulls::place_roll_log(&mut self.config.roll_log, &mut self.roll_entry, ui);
                     }
+                    layout::place_item_grid(
+                        &mut self.config,
+                        &self.pbhl_honors,
+                        self.selected_raid,
+                        ui,
+                    );
                     if self.config.app_settings.current_ui_tab == UiTab::PBHL {
-                        egui::Grid::new("pbhl_item_grid")
-                            .spacing((
-                                self.config.app_settings.grid_spacing_x,
-                                self.config.app_settings.grid_spacing_y,
-                            ))
-                            .show(ui, |ui| {
-                                ui.style_mut().wrap = Some(false);
-                                if !self.config.app_settings.vertical_grid {
-                                    let pbhl_drops = vec![
-                                        Item::NoDrop,
-                                        Item::GoldBrick,
-                                        Item::CoronationRing,
-                                        Item::LineageRing,
-                                        Item::IntricacyRing,
-                                    ];
-                                    for (pos, drop) in pbhl_drops.iter().enumerate() {
-                                        let chest = match drop {
-                                            Item::NoDrop => ChestType::None,
-                                            _ => ChestType::Blue,
-                                        };
-                                        if self.config.app_settings.active_items[pos + 13] {
-                                            ui.horizontal(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::PBHL,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 2 {
-                                            ui.end_row();
-                                        }
-                                    }
-                                } else {
-                                    let pbhl_drops = vec![
-                                        Item::NoDrop,
-                                        Item::GoldBrick,
-                                        Item::CoronationRing,
-                                        Item::LineageRing,
-                                        Item::IntricacyRing,
-                                    ];
-                                    for (pos, drop) in pbhl_drops.iter().enumerate() {
-                                        let chest = match drop {
-                                            Item::NoDrop => ChestType::None,
-                                            _ => ChestType::Blue,
-                                        };
-                                        if self.config.app_settings.active_items[pos + 13] {
-                                            ui.vertical(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::PBHL,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 2 {
-                                            ui.end_row();
-                                        }
-                                    }
-                                }
-                            });
-
                         ui.add_space(20.);
                         ui.heading("Honors");
                         ui.label("Select the closest match rounding down.");
@@ -1483,511 +981,7 @@ impl epi::App for AppDorothy {
                                 );
                             });
                     }
-                    if self.config.app_settings.current_ui_tab == UiTab::GOHL {
-                        egui::Grid::new("gohl_item_grid")
-                            .spacing((
-                                self.config.app_settings.grid_spacing_x,
-                                self.config.app_settings.grid_spacing_y,
-                            ))
-                            .show(ui, |ui| {
-                                ui.style_mut().wrap = Some(false);
-                                if !self.config.app_settings.vertical_grid {
-                                    let gohl_drops = vec![
-                                        Item::NoDrop,
-                                        Item::VerdantAzurite,
-                                        Item::SilverCentrum,
-                                        Item::GoldBrick,
-                                        Item::CoronationRing,
-                                        Item::ChampionMerit,
-                                        Item::LineageRing,
-                                        Item::SupremeMerit,
-                                        Item::IntricacyRing,
-                                        Item::LegendaryMerit,
-                                    ];
-                                    for (pos, drop) in gohl_drops.iter().enumerate() {
-                                        let chest = match drop {
-                                            Item::NoDrop => ChestType::None,
-                                            _ => ChestType::Blue,
-                                        };
-                                        if self.config.app_settings.active_items[pos + 18] {
-                                            ui.horizontal(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::GOHL,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 3 || pos == 5 || pos == 7 || pos == 9 {
-                                            ui.end_row();
-                                        }
-                                    }
-                                } else {
-                                    let gohl_drops = vec![
-                                        Item::NoDrop,
-                                        Item::VerdantAzurite,
-                                        Item::SilverCentrum,
-                                        Item::GoldBrick,
-                                        Item::CoronationRing,
-                                        Item::ChampionMerit,
-                                        Item::LineageRing,
-                                        Item::SupremeMerit,
-                                        Item::IntricacyRing,
-                                        Item::LegendaryMerit,
-                                    ];
-                                    for (pos, drop) in gohl_drops.iter().enumerate() {
-                                        let chest = match drop {
-                                            Item::NoDrop => ChestType::None,
-                                            _ => ChestType::Blue,
-                                        };
-                                        if self.config.app_settings.active_items[pos + 18] {
-                                            ui.vertical(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::GOHL,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 3 || pos == 5 || pos == 7 || pos == 9 {
-                                            ui.end_row();
-                                        }
-                                    }
-                                }
-                            });
-                    }
-                    if self.config.app_settings.current_ui_tab == UiTab::Hosts {
-                        egui::Grid::new("hosts_item_grid")
-                            .spacing((
-                                self.config.app_settings.grid_spacing_x,
-                                self.config.app_settings.grid_spacing_y,
-                            ))
-                            .show(ui, |ui| {
-                                ui.style_mut().wrap = Some(false);
-                                if !self.config.app_settings.vertical_grid {
-                                    if self.config.app_settings.active_items[28] {
-                                        ui.horizontal(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::UBHL,
-                                                ChestType::Host,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    if self.config.app_settings.active_items[29] {
-                                        ui.horizontal(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::UBHL,
-                                                ChestType::Flip,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    if self.config.app_settings.active_items[30] {
-                                        ui.horizontal(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::PBHL,
-                                                ChestType::Host,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    if self.config.app_settings.active_items[31] {
-                                        ui.horizontal(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::Xeno,
-                                                ChestType::Flip,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    ui.end_row();
-                                    if self.config.app_settings.active_items_2[0] {
-                                        ui.horizontal(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::Huanglong,
-                                                ChestType::Host,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    if self.config.app_settings.active_items_2[1] {
-                                        ui.horizontal(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::Qilin,
-                                                ChestType::Host,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    if self.config.app_settings.active_items_2[2] {
-                                        ui.horizontal(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::HLQL,
-                                                ChestType::Host,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                } else {
-                                    if self.config.app_settings.active_items[28] {
-                                        ui.vertical(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::UBHL,
-                                                ChestType::Host,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    if self.config.app_settings.active_items[29] {
-                                        ui.vertical(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::UBHL,
-                                                ChestType::Flip,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    if self.config.app_settings.active_items[30] {
-                                        ui.vertical(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::PBHL,
-                                                ChestType::Host,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    if self.config.app_settings.active_items[31] {
-                                        ui.vertical(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::Xeno,
-                                                ChestType::Flip,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    ui.end_row();
-                                    if self.config.app_settings.active_items_2[0] {
-                                        ui.vertical(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::Huanglong,
-                                                ChestType::Host,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    if self.config.app_settings.active_items_2[1] {
-                                        ui.vertical(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::Qilin,
-                                                ChestType::Host,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                    if self.config.app_settings.active_items_2[2] {
-                                        ui.vertical(|ui| {
-                                            place_image_button_combo(
-                                                Item::GoldBrick,
-                                                Raid::HLQL,
-                                                ChestType::Host,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            );
-                                        });
-                                    }
-                                }
-                            });
-                    }
                     if self.config.app_settings.current_ui_tab == UiTab::SixDragons {
-                        egui::Grid::new("six_dragons_item_grid")
-                            .spacing((
-                                self.config.app_settings.grid_spacing_x,
-                                self.config.app_settings.grid_spacing_y,
-                            ))
-                            .show(ui, |ui| {
-                                ui.style_mut().wrap = Some(false);
-                                if !self.config.app_settings.vertical_grid {
-                                    let six_dragon_drops = vec![
-                                        Item::NoDrop,
-                                        Item::BrimstoneEarrings,
-                                        Item::PermafrostEarrings,
-                                        Item::BrickearthEarrings,
-                                        Item::JetstreamEarrings,
-                                        Item::SunbeamEarrings,
-                                        Item::NightshadeEarrings,
-                                        Item::EternitySand,
-                                    ];
-                                    for (pos, drop) in six_dragon_drops.iter().enumerate() {
-                                        let chest = match drop {
-                                            Item::NoDrop => ChestType::None,
-                                            _ => ChestType::Blue,
-                                        };
-                                        if pos == 0 {
-                                            ui.horizontal(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    self.selected_raid,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 1 {
-                                            ui.horizontal(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Wilnas,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 2 {
-                                            ui.horizontal(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Wamdus,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 3 {
-                                            ui.horizontal(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Galleon,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 4 {
-                                            ui.horizontal(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Ewiyar,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 5 {
-                                            ui.horizontal(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::LuWoh,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 6 {
-                                            ui.horizontal(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Fediel,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 7 {
-                                            ui.horizontal(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    self.selected_raid,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                    if pos == 0 || pos == 3 || pos == 6 {
-                                        ui.end_row();
-                                    }
-                                    }
-                                } else {
-                                    let six_dragon_drops = vec![
-                                        Item::NoDrop,
-                                        Item::BrimstoneEarrings,
-                                        Item::PermafrostEarrings,
-                                        Item::BrickearthEarrings,
-                                        Item::JetstreamEarrings,
-                                        Item::SunbeamEarrings,
-                                        Item::NightshadeEarrings,
-                                        Item::EternitySand,
-                                    ];
-                                    for (pos, drop) in six_dragon_drops.iter().enumerate() {
-                                        let chest = match drop {
-                                            Item::NoDrop => ChestType::None,
-                                            _ => ChestType::Blue,
-                                        };
-                                        if pos == 0 {
-                                            ui.vertical(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    self.selected_raid,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 1 {
-                                            ui.vertical(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Wilnas,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 2 {
-                                            ui.vertical(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Wamdus,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 3 {
-                                            ui.vertical(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Galleon,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 4 {
-                                            ui.vertical(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Ewiyar,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 5 {
-                                            ui.vertical(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::LuWoh,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 6 {
-                                            ui.vertical(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    Raid::Fediel,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 7 {
-                                            ui.vertical(|ui| {
-                                                place_image_button_combo(
-                                                    *drop,
-                                                    self.selected_raid,
-                                                    chest,
-                                                    &self.pbhl_honors,
-                                                    &mut self.config,
-                                                    ui,
-                                                )
-                                            });
-                                        }
-                                        if pos == 0 || pos == 3 || pos == 6 {
-                                            ui.end_row();
-                                        }
-                                    }
-                                }
-                            });
-
                         ui.add_space(20.);
                         ui.heading("Current Raid");
                         ui.label("Select the current raid you are farming.");
@@ -2036,58 +1030,6 @@ impl epi::App for AppDorothy {
                             });
                     }
                     if self.config.app_settings.current_ui_tab == UiTab::EternitySand {
-                        egui::Grid::new("six_dragons_item_grid")
-                            .spacing((
-                                self.config.app_settings.grid_spacing_x,
-                                self.config.app_settings.grid_spacing_y,
-                            ))
-                            .show(ui, |ui| {
-                                ui.style_mut().wrap = Some(false);
-                                if !self.config.app_settings.vertical_grid {
-                                    let sand_drops = vec![
-                                        Item::EternitySand,
-                                    ];
-                                    for (pos, drop) in sand_drops.iter().enumerate() {
-                                        let chest = match drop {
-                                            Item::NoDrop => ChestType::None,
-                                            _ => ChestType::Gold,
-                                        };
-                                        ui.horizontal(|ui| {
-                                            place_image_button_combo(
-                                                *drop,
-                                                self.selected_raid,
-                                                chest,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            )
-                                        });
-                                        
-                                    }
-                                } else {
-                                    let sand_drops = vec![
-                                        Item::EternitySand,
-                                    ];
-                                    for (pos, drop) in sand_drops.iter().enumerate() {
-                                        let chest = match drop {
-                                            Item::NoDrop => ChestType::None,
-                                            _ => ChestType::Gold,
-                                        };
-                                      
-                                        ui.vertical(|ui| {
-                                            place_image_button_combo(
-                                                *drop,
-                                                self.selected_raid,
-                                                chest,
-                                                &self.pbhl_honors,
-                                                &mut self.config,
-                                                ui,
-                                            )
-                                        });
-                                    }
-                                }
-                            });
-
                         ui.add_space(20.);
                         ui.heading("Current Raid");
                         ui.label("Select the current raid you are farming.");
@@ -2228,35 +1170,95 @@ impl epi::App for AppDorothy {
                 });
 
             // Hotkey controls
-            if ui.input().key_pressed(egui::Key::R) && ui.input().modifiers.shift_only() {
-                self.config.app_settings.right_panel_visible = !self.config.app_settings.right_panel_visible
-            }
-
-            if ui.input().key_pressed(egui::Key::D) && ui.input().modifiers.shift_only() {
-                self.config.app_settings.left_panel_visible = !self.config.app_settings.left_panel_visible
-            }
-
-            if ui.input().key_pressed(egui::Key::A) && ui.input().modifiers.shift_only() {
-                self.config.app_settings.always_on_top = !self.config.app_settings.always_on_top
-            }
+            self.process_hotkeys(ctx);
+        });
+        if self.export_window_open {
+            let mut export_window_open = self.export_window_open;
+            egui::Window::new("Filtered Export")
+                .open(&mut export_window_open)
+                .vscroll(true)
+                .show(ctx, |ui| {
+                    let filter = &mut self.config.app_settings.export_filter;
+                    ui.horizontal(|ui| {
+                        ui.label("From");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut filter.from_date)
+                                .hint_text("YYYY-MM-DD")
+                                .desired_width(100.),
+                        );
+                        ui.label("To");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut filter.to_date)
+                                .hint_text("YYYY-MM-DD")
+                                .desired_width(100.),
+                        );
+                    });
+                    if let Err(e) = filter.date_range() {
+                        ui.colored_label(egui::Color32::from_rgb(255, 90, 90), e);
+                    }
+                    ui.checkbox(&mut filter.session_only, "Only drops from this session");
+                    ui.add_space(5.);
+                    ui.label("Leave everything unticked to export all raids and items.");
 
-            if ui.input().key_pressed(egui::Key::L) && ui.input().modifiers.shift_only() {
-                self.config.app_settings.dark_mode = !self.config.app_settings.dark_mode
-            }
-            
-            if ui.input().key_pressed(egui::Key::E) && ui.input().modifiers.shift_only() {
-                let _ = export(self.config.droplog.clone());
-                if self.config.app_settings.reset_on_export {
-                    self.config.droplog.drop = DropLog::reset();
-                }
-            }
+                    let mut logged_raids: Vec<Raid> = filter.raids.clone();
+                    let mut logged_items: Vec<Item> = filter.items.clone();
+                    for drop in self.config.droplog.drop.iter() {
+                        if !logged_raids.contains(&drop.raid) {
+                            logged_raids.push(drop.raid);
+                        }
+                        if !logged_items.contains(&drop.item) {
+                            logged_items.push(drop.item);
+                        }
+                    }
+                    ui.collapsing("Raids", |ui| {
+                        for raid in logged_raids {
+                            place_filter_checkbox(raid, raid.to_string(), &mut filter.raids, ui);
+                        }
+                    });
+                    ui.collapsing("Items", |ui| {
+                        for item in logged_items {
+                            place_filter_checkbox(item, item.to_string(), &mut filter.items, ui);
+                        }
+                    });
+                    ui.add_space(5.);
 
-            if ui.input().key_pressed(egui::Key::S) && ui.input().modifiers.shift_only() {
-                self.config.app_settings.toggle_active_items = !self.config.app_settings.toggle_active_items
-            }
-            
-            
-        });
+                    let filtered_drops = match filter.apply(&self.config.droplog.drop, &self.session_start) {
+                        Ok(filtered_drops) => filtered_drops,
+                        Err(_) => {
+                            ui.label("Fix the dates to export.");
+                            return;
+                        }
+                    };
+                    ui.label(format!("{} drops match.", filtered_drops.len()));
+                    ui.horizontal(|ui| {
+                        if ui.button("Export Drops").clicked() {
+                            if let Err(e) = export(
+                                DropLog {
+                                    drop: filtered_drops,
+                                },
+                                self.config.app_settings.export_format,
+                                &self.config.app_settings.highlight_rules,
+                            ) {
+                                self.export_status = Some(format!("Dorothy couldn't export the drops: {}", e));
+                            }
+                        }
+                        if ui.button("Export Summary").clicked() {
+                            if let Ok(drops) = filter.apply_to_kills(&self.config.droplog.drop, &self.session_start) {
+                                if let Err(e) = export::export_summary(
+                                    &drops,
+                                    &filter.items,
+                                    self.config.app_settings.droprate_by_kills,
+                                    self.config.app_settings.export_format,
+                                    &self.config.app_settings.highlight_rules,
+                                ) {
+                                    self.export_status = Some(format!("Dorothy couldn't export the summary: {}", e));
+                                }
+                            }
+                        }
+                    });
+                });
+            self.export_window_open = export_window_open;
+        }
         if !self.config.app_settings.button_label_combo[0]
             && !self.config.app_settings.button_label_combo[1]
         {
@@ -2346,187 +1348,9 @@ impl epi::App for AppDorothy {
                 ui.label("Grid Y Spacing.".to_string());
                 ui.add(egui::Slider::new(&mut self.config.app_settings.grid_spacing_y, 0.0..=50.0));
                 ui.add_space(5.);
-                ui.label("The grid isn't smart enough to adjust but you can toggle specific items off here.".to_string());
-                ui.heading("Akasha");
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[0],
-                            "Show No Blue Box",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[1],
-                            "Show Hollow Key",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[2],
-                            "Show Silver Centrum",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[3],
-                            "Show Gold Bar",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[4],
-                            "Show Coronation Ring",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[7],
-                            "Show Lineage Ring",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[10],
-                            "Show Intricacy Ring",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[5],
-                            "Show Champion Merit",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[8],
-                            "Show Supreme Merit",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[11],
-                            "Show Legendary Merit",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[6],
-                            "Show +1 Mark",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[9],
-                            "Show +2 Mark",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[12],
-                            "Show +3 Mark",
-                        );
-                ui.heading("PBHL");
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[13],
-                            "Show No Blue Box",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[14],
-                            "Show Gold Bar",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[15],
-                            "Show Coronation Ring",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[16],
-                            "Show Lineage Ring",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[17],
-                            "Show Lineage Ring",
-                        );
-                ui.heading("GOHL");
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[18],
-                            "Show No Blue Box",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[19],
-                            "Show Verdant Azurite",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[20],
-                            "Show Silver Centrum",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[21],
-                            "Show Gold Bar",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[22],
-                            "Show Coronation Ring",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[24],
-                            "Show Lineage Ring",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[26],
-                            "Show Intricacy Ring",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[23],
-                            "Show Champion Merit",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[25],
-                            "Show Supreme Merit",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[27],
-                            "Show Legendary Merit",
-                        );
-                ui.heading("Hosts");
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[28],
-                            "Show UBHL Host Gold Bar",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[29],
-                            "Show UBHL Flip Gold Bar",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[30],
-                            "Show PBHL Host Gold Bar",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items[31],
-                            "Show Xeno Gold Bar",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items_2[0],
-                            "Show Huanglong Gold Bar",
-                        );
-                    ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items_2[1],
-                            "Show Qilin Gold Bar",
-                        );
-                        ui
-                        .checkbox(
-                            &mut self.config.app_settings.active_items_2[2],
-                            "Show HLQL Gold Bar",
-                        );
-
+                if ui.button("Edit Item Layout...").clicked() {
+                    self.layout_editor.open = true;
+                }
             });
         }
     }
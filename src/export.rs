@@ -0,0 +1,501 @@
+use crate::highlight::{highlight_name, HighlightRule};
+use crate::pulls::RollLog;
+use crate::{
+    drop_rate, format_rate, item_count, no_drop_count, total_kills, ChestType, DropLog, Item,
+    ItemDrop, Raid,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+#[cfg(not(target_arch = "wasm32"))]
+use chrono::{DateTime, Local};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::OpenOptions;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::{BufWriter, Write};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+#[cfg(target_arch = "wasm32")]
+use eframe::wasm_bindgen::{closure::Closure, JsCast, JsValue};
+#[cfg(target_arch = "wasm32")]
+use std::sync::{Arc, Mutex};
+
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match *self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match *self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Json => "application/json",
+        }
+    }
+
+    /// Guesses the format of an imported file from its extension, defaulting to CSV.
+    pub fn from_file_name(file_name: &str) -> Self {
+        if file_name.to_lowercase().ends_with(".json") {
+            ExportFormat::Json
+        } else {
+            ExportFormat::Csv
+        }
+    }
+}
+
+pub fn export_to_bytes<T: Serialize>(
+    rows: &[T],
+    format: ExportFormat,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    match format {
+        ExportFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(vec![]);
+            for row in rows {
+                wtr.serialize(row)?;
+            }
+            wtr.flush()?;
+            Ok(wtr.into_inner()?)
+        }
+        ExportFormat::Json => Ok(serde_json::to_vec_pretty(rows)?),
+    }
+}
+
+pub fn import_from_bytes(
+    bytes: &[u8],
+    format: ExportFormat,
+) -> Result<Vec<ItemDrop>, Box<dyn Error>> {
+    let mut drops: Vec<ItemDrop> = match format {
+        ExportFormat::Csv => {
+            let mut rdr = csv::Reader::from_reader(bytes);
+            let mut drops = vec![];
+            for record in rdr.deserialize() {
+                drops.push(record?);
+            }
+            drops
+        }
+        ExportFormat::Json => serde_json::from_slice(bytes)?,
+    };
+    // Empty honors come back from a .csv as `None` but the UI expects `Some`
+    for drop in drops.iter_mut() {
+        drop.honors.get_or_insert_with(String::new);
+    }
+    Ok(drops)
+}
+
+/// Name of an exported file, e.g. `dorothy-120-drops-2022-04-01.csv`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export_file_name(rows: usize, contents: &str, extension: &str) -> String {
+    let export_time: DateTime<Local> = Local::now();
+    format!(
+        "dorothy-{}-{}-{}.{}",
+        rows,
+        contents,
+        export_time.format("%Y-%m-%d"),
+        extension
+    )
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn export_file_name(rows: usize, contents: &str, extension: &str) -> String {
+    let export_time = js_sys::Date::new_0();
+    format!(
+        "dorothy-{}-{}-{}-{:02}-{:02}.{}",
+        rows,
+        contents,
+        export_time.get_full_year(),
+        export_time.get_month() + 1,
+        export_time.get_date(),
+        extension
+    )
+}
+
+/// Writes a file into `./exports/`, replacing an existing file of the same name so every export
+/// can be imported again.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_export(
+    file_name: &str,
+    _mime_type: &str,
+    contents: &[u8],
+) -> Result<(), Box<dyn Error>> {
+    if !Path::new("./exports/").exists() {
+        crate::create_path("./exports/")?;
+    }
+    let str_path = format!("./exports/{}", file_name);
+
+    if Path::new("./exports/").exists() {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&str_path)?;
+        let mut wtr = BufWriter::new(file);
+        wtr.write_all(contents)?;
+    }
+    Ok(())
+}
+
+/// The webapp can't write to disk so the export is handed to the browser as a download instead.
+#[cfg(target_arch = "wasm32")]
+pub fn save_export(
+    file_name: &str,
+    mime_type: &str,
+    contents: &[u8],
+) -> Result<(), Box<dyn Error>> {
+    download(file_name, mime_type, contents).map_err(|e| format!("{:?}", e))?;
+    Ok(())
+}
+
+/// A drop as it's exported, with the name of the highlight rule it matches. Imports ignore the
+/// highlight, so exports can be imported again.
+#[derive(Serialize)]
+pub struct DropRow<'a> {
+    pub drop_id: u32,
+    pub date_obtained: &'a str,
+    pub raid: Raid,
+    pub item: Item,
+    pub chest: ChestType,
+    pub honors: &'a Option<String>,
+    pub quantity: u32,
+    pub kill_id: Option<u32>,
+    pub highlight: String,
+}
+
+impl<'a> DropRow<'a> {
+    pub fn new(drop: &'a ItemDrop, rules: &[HighlightRule]) -> Self {
+        Self {
+            drop_id: drop.drop_id,
+            date_obtained: &drop.date_obtained,
+            raid: drop.raid,
+            item: drop.item,
+            chest: drop.chest,
+            honors: &drop.honors,
+            quantity: drop.quantity,
+            kill_id: drop.kill_id,
+            highlight: highlight_name(rules, drop.item, drop.raid, drop.chest),
+        }
+    }
+}
+
+pub fn export(
+    droplog: DropLog,
+    format: ExportFormat,
+    rules: &[HighlightRule],
+) -> Result<(), Box<dyn Error>> {
+    let rows: Vec<DropRow<'_>> = droplog
+        .drop
+        .iter()
+        .map(|x| DropRow::new(x, rules))
+        .collect();
+    save_export(
+        &export_file_name(droplog.drop.len(), "drops", format.extension()),
+        format.mime_type(),
+        &export_to_bytes(&rows, format)?,
+    )
+}
+
+pub fn export_rolls(roll_log: &RollLog, format: ExportFormat) -> Result<(), Box<dyn Error>> {
+    save_export(
+        &export_file_name(roll_log.rolls.len(), "rolls", format.extension()),
+        format.mime_type(),
+        &export_to_bytes(&roll_log.rolls, format)?,
+    )
+}
+
+pub fn export_summary(
+    drops: &[ItemDrop],
+    items: &[Item],
+    droprate_by_kills: bool,
+    format: ExportFormat,
+    rules: &[HighlightRule],
+) -> Result<(), Box<dyn Error>> {
+    let summary = summarize(drops, items, droprate_by_kills, rules);
+    save_export(
+        &export_file_name(drops.len(), "summary", format.extension()),
+        format.mime_type(),
+        &export_to_bytes(&summary, format)?,
+    )
+}
+
+/// Decides which drops make it into a filtered export. Empty fields don't filter anything.
+#[derive(PartialEq, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct ExportFilter {
+    pub from_date: String,
+    pub to_date: String,
+    pub raids: Vec<Raid>,
+    pub items: Vec<Item>,
+    pub session_only: bool,
+}
+
+/// A date typed as `YYYY-MM-DD`, or `None` when nothing was typed.
+fn parse_date(label: &str, text: &str) -> Result<Option<NaiveDate>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|_| format!("{} should be a date like 2022-04-01", label))
+}
+
+/// When a drop was logged or Dorothy was opened, as written by `get_time()`. Anything after the
+/// seconds, like the milliseconds and time zone older webapp versions wrote, is ignored.
+pub fn parse_timestamp(timestamp: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(timestamp.get(..19)?, "%Y-%m-%d %H:%M:%S").ok()
+}
+
+/// When `drop` was logged. Drops imported with only a date count as logged at midnight.
+pub fn logged_at(drop: &ItemDrop) -> Option<NaiveDateTime> {
+    parse_timestamp(&drop.date_obtained).or_else(|| {
+        NaiveDate::parse_from_str(drop.date_obtained.get(..10)?, "%Y-%m-%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)
+    })
+}
+
+impl ExportFilter {
+    /// The From and To dates, or why one of them can't be read.
+    pub fn date_range(&self) -> Result<(Option<NaiveDate>, Option<NaiveDate>), String> {
+        Ok((
+            parse_date("From", &self.from_date)?,
+            parse_date("To", &self.to_date)?,
+        ))
+    }
+
+    /// Drops whose date can't be read only match when no dates are picked.
+    fn matches(
+        &self,
+        drop: &ItemDrop,
+        (from_date, to_date): (Option<NaiveDate>, Option<NaiveDate>),
+        session_start: Option<NaiveDateTime>,
+    ) -> bool {
+        let logged = logged_at(drop);
+        let in_range = match logged.map(|x| x.date()) {
+            Some(date) => {
+                !matches!(from_date, Some(from_date) if date < from_date)
+                    && !matches!(to_date, Some(to_date) if date > to_date)
+            }
+            None => from_date.is_none() && to_date.is_none(),
+        };
+        let in_session = match (logged, session_start) {
+            (Some(logged), Some(session_start)) => logged >= session_start,
+            _ => false,
+        };
+        in_range
+            && (self.raids.is_empty() || self.raids.contains(&drop.raid))
+            && (self.items.is_empty() || self.items.contains(&drop.item))
+            && (!self.session_only || in_session)
+    }
+
+    /// The drops the filter lets through. `session_start` is when Dorothy was opened, from
+    /// `get_time()`.
+    pub fn apply(&self, drops: &[ItemDrop], session_start: &str) -> Result<Vec<ItemDrop>, String> {
+        let date_range = self.date_range()?;
+        let session_start = parse_timestamp(session_start);
+        Ok(drops
+            .iter()
+            .filter(|x| self.matches(x, date_range, session_start))
+            .cloned()
+            .collect())
+    }
+
+    /// Same as `apply` but keeps every item so kills and no drops are still counted for a summary.
+    pub fn apply_to_kills(
+        &self,
+        drops: &[ItemDrop],
+        session_start: &str,
+    ) -> Result<Vec<ItemDrop>, String> {
+        ExportFilter {
+            items: vec![],
+            ..self.clone()
+        }
+        .apply(drops, session_start)
+    }
+}
+
+/// One line of an aggregated export: how often an item dropped from a raid.
+#[derive(Clone, Debug, Serialize)]
+pub struct SummaryRow {
+    pub raid: Raid,
+    pub kills: usize,
+    pub no_drops: usize,
+    pub item: Item,
+    pub chest: ChestType,
+    pub count: usize,
+    pub rate: String,
+    pub highlight: String,
+}
+
+/// Aggregates drops per raid using the same counts as the Drop Totals panel.
+/// Only `items` get a row, or every item when `items` is empty.
+pub fn summarize(
+    drops: &[ItemDrop],
+    items: &[Item],
+    droprate_by_kills: bool,
+    rules: &[HighlightRule],
+) -> Vec<SummaryRow> {
+    let mut raids: Vec<Raid> = vec![];
+    for drop in drops {
+        if !raids.contains(&drop.raid) {
+            raids.push(drop.raid);
+        }
+    }
+
+    let mut summary = vec![];
+    for raid in raids {
+        let mut raid_items: Vec<(Item, ChestType)> = vec![];
+        for drop in drops.iter().filter(|x| x.raid == raid) {
+            if !raid_items.contains(&(drop.item, drop.chest))
+                && (items.is_empty() || items.contains(&drop.item))
+            {
+                raid_items.push((drop.item, drop.chest));
+            }
+        }
+        for (item, chest) in raid_items {
+            summary.push(SummaryRow {
+                raid,
+                kills: total_kills(drops, raid),
+                no_drops: no_drop_count(drops, raid),
+                item,
+                chest,
+                count: item_count(drops, raid, item, chest),
+                rate: drop_rate(drops, raid, item, chest, droprate_by_kills)
+                    .map(format_rate)
+                    .unwrap_or_default(),
+                highlight: highlight_name(rules, item, raid, chest),
+            });
+        }
+    }
+    summary
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn download(file_name: &str, mime_type: &str, contents: &[u8]) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(contents));
+    let mut properties = web_sys::BlobPropertyBag::new();
+    properties.type_(mime_type);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &properties)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let window = web_sys::window().ok_or("Dorothy couldn't find the page to download from")?;
+    let document = window
+        .document()
+        .ok_or("Dorothy couldn't find the page to download from")?;
+    let anchor = document
+        .create_element("a")?
+        .dyn_into::<web_sys::HtmlAnchorElement>()?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    // Firefox and Safari start the download after the click returns and cancel it if the URL is
+    // already gone, so it's revoked a little later instead
+    let revoke = Closure::once_into_js(move || {
+        let _ = web_sys::Url::revoke_object_url(&url);
+    });
+    window.set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), 10_000)?;
+    Ok(())
+}
+
+/// File name and contents of a file picked in the browser, waiting for the next frame to pick it up.
+#[cfg(target_arch = "wasm32")]
+pub type PendingImport = Arc<Mutex<Option<(String, Vec<u8>)>>>;
+
+/// Opens the browser's file picker. The file is read asynchronously and dropped into `pending`.
+#[cfg(target_arch = "wasm32")]
+pub fn pick_import_file(pending: PendingImport, accept: &str) -> Result<(), JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("Dorothy couldn't find the page to import from")?;
+    let input = document
+        .create_element("input")?
+        .dyn_into::<web_sys::HtmlInputElement>()?;
+    input.set_type("file");
+    input.set_accept(accept);
+
+    let picker = input.clone();
+    let on_change = Closure::wrap(Box::new(move |_: web_sys::Event| {
+        let file = match picker.files().and_then(|files| files.get(0)) {
+            Some(file) => file,
+            None => return,
+        };
+        let reader = match web_sys::FileReader::new() {
+            Ok(reader) => reader,
+            Err(_) => return,
+        };
+        let file_name = file.name();
+        let pending = pending.clone();
+        let loaded = reader.clone();
+        let on_load = Closure::wrap(Box::new(move |_: web_sys::Event| {
+            if let Ok(result) = loaded.result() {
+                let contents = js_sys::Uint8Array::new(&result).to_vec();
+                *pending.lock().unwrap() = Some((file_name.clone(), contents));
+            }
+        }) as Box<dyn FnMut(web_sys::Event)>);
+        reader.set_onload(Some(on_load.as_ref().unchecked_ref()));
+        on_load.forget();
+        let _ = reader.read_as_array_buffer(&file);
+    }) as Box<dyn FnMut(web_sys::Event)>);
+    input.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+    on_change.forget();
+
+    input.click();
+    Ok(())
+}
+
+impl DropLog {
+    /// Adds previously exported drops to the log, giving them fresh ids so they can be removed
+    /// individually. They're put where their dates belong among the logged drops, since the log
+    /// is expected to be oldest first. Drops whose date can't be read go after everything else.
+    pub fn import(&mut self, drops: Vec<ItemDrop>) -> usize {
+        let next_id = self
+            .drop
+            .iter()
+            .map(|x| x.drop_id + 1)
+            .max()
+            .unwrap_or_default();
+        // Kills are kept together but can't share an id with a kill that's already logged
+        let next_kill_id = self
+            .drop
+            .iter()
+            .filter_map(|x| x.kill_id.map(|kill_id| kill_id + 1))
+            .max()
+            .unwrap_or_default();
+        let imported = drops.len();
+        let mut dated = vec![];
+        let mut undated = vec![];
+        for (offset, mut drop) in drops.into_iter().enumerate() {
+            drop.drop_id = next_id + offset as u32;
+            drop.kill_id = drop.kill_id.map(|kill_id| next_kill_id + kill_id);
+            match logged_at(&drop) {
+                Some(logged) => dated.push((logged, drop)),
+                None => undated.push(drop),
+            }
+        }
+        // Stable, so drops logged at the same time keep their order
+        dated.sort_by_key(|(logged, _)| *logged);
+
+        let mut dated = dated.into_iter().peekable();
+        for drop in std::mem::take(&mut self.drop) {
+            if let Some(logged) = logged_at(&drop) {
+                while let Some((_, imported_drop)) = dated.next_if(|(x, _)| *x < logged) {
+                    self.drop.push(imported_drop);
+                }
+            }
+            self.drop.push(drop);
+        }
+        self.drop.extend(dated.map(|(_, drop)| drop));
+        self.drop.extend(undated);
+        imported
+    }
+}
@@ -0,0 +1,372 @@
+use crate::format_rate;
+use crate::pulls::{DOUBLED_SSR_RATE, SPARK_PULLS, SSR_RATE};
+use eframe::egui::{self, Ui};
+use serde::{Deserialize, Serialize};
+
+/// Settings of the "What Are My Odds?" panel.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OddsSettings {
+    /// Legend Festival / Flash Gala rates instead of the normal 3%.
+    pub doubled_rates: bool,
+    /// Chance of the specific rate-up SSR on a single draw, as a percentage.
+    pub rate_up_percent: f32,
+    pub monte_carlo: bool,
+    pub trials: u32,
+}
+
+impl Default for OddsSettings {
+    fn default() -> Self {
+        Self {
+            doubled_rates: false,
+            rate_up_percent: 0.3,
+            monte_carlo: false,
+            trials: 10_000,
+        }
+    }
+}
+
+impl OddsSettings {
+    pub fn ssr_rate(&self) -> f64 {
+        if self.doubled_rates {
+            DOUBLED_SSR_RATE as f64
+        } else {
+            SSR_RATE as f64
+        }
+    }
+
+    /// The rate-up SSR is one of the SSRs, so its rate can't be higher than the SSR rate.
+    pub fn rate_up_rate(&self) -> f64 {
+        (self.rate_up_percent as f64 / 100.).clamp(0., self.ssr_rate())
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Default, Debug)]
+pub struct Odds {
+    /// Chance of pulling the rate-up SSR at least once before spending enough pulls to spark it.
+    pub rate_up_before_spark: f64,
+    /// Chance of at least one SSR of any kind from every pull.
+    pub any_ssr: f64,
+    pub expected_ssrs: f64,
+}
+
+/// Odds of `pulls` draws where every draw is independent:
+///
+/// * the chance of never pulling something with rate `p` in `n` draws is `(1 - p)^n`,
+///   so the chance of pulling it at least once is `1 - (1 - p)^n`
+/// * only the draws before a spark count for the rate-up SSR, since the spark gets it anyway
+/// * the expected number of SSRs is `n * ssr_rate`
+pub fn calculate_odds(pulls: u32, ssr_rate: f64, rate_up_rate: f64) -> Odds {
+    let pulls_before_spark = pulls.min(SPARK_PULLS) as i32;
+    Odds {
+        rate_up_before_spark: 1. - (1. - rate_up_rate).powi(pulls_before_spark),
+        any_ssr: 1. - (1. - ssr_rate).powf(pulls as f64),
+        expected_ssrs: pulls as f64 * ssr_rate,
+    }
+}
+
+/// SplitMix64, which is plenty random for simulating draws without pulling in a crate for it.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..1`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Draws simulated so far, which a simulation can keep adding runs to.
+#[derive(PartialEq, Copy, Clone, Default, Debug)]
+pub struct OddsTally {
+    pub trials: u32,
+    rate_up_runs: u32,
+    any_ssr_runs: u32,
+    total_ssrs: u64,
+}
+
+impl OddsTally {
+    /// Draws `pulls` times in each of `trials` more runs.
+    pub fn run(
+        &mut self,
+        pulls: u32,
+        ssr_rate: f64,
+        rate_up_rate: f64,
+        trials: u32,
+        rng: &mut Rng,
+    ) {
+        for _ in 0..trials {
+            let mut got_rate_up = false;
+            let mut ssrs = 0;
+            for pull in 0..pulls {
+                let draw = rng.next_f64();
+                if draw < rate_up_rate && pull < SPARK_PULLS {
+                    got_rate_up = true;
+                }
+                if draw < ssr_rate {
+                    ssrs += 1;
+                }
+            }
+            if got_rate_up {
+                self.rate_up_runs += 1;
+            }
+            if ssrs > 0 {
+                self.any_ssr_runs += 1;
+            }
+            self.total_ssrs += ssrs;
+        }
+        self.trials += trials;
+    }
+
+    pub fn odds(&self) -> Odds {
+        if self.trials == 0 {
+            return Odds::default();
+        }
+        Odds {
+            rate_up_before_spark: self.rate_up_runs as f64 / self.trials as f64,
+            any_ssr: self.any_ssr_runs as f64 / self.trials as f64,
+            expected_ssrs: self.total_ssrs as f64 / self.trials as f64,
+        }
+    }
+}
+
+/// Estimates the same odds as `calculate_odds` by drawing `pulls` times in each of `trials` runs.
+pub fn simulate_odds(
+    pulls: u32,
+    ssr_rate: f64,
+    rate_up_rate: f64,
+    trials: u32,
+    rng: &mut Rng,
+) -> Odds {
+    let mut tally = OddsTally::default();
+    tally.run(pulls, ssr_rate, rate_up_rate, trials, rng);
+    tally.odds()
+}
+
+/// A seed that changes every time Dorothy simulates, taken from the current time.
+pub fn time_seed() -> u64 {
+    crate::get_time()
+        .bytes()
+        .fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+        })
+}
+
+/// Draws a simulation makes each frame. A full spark simulated 100 000 times is 30 million
+/// draws, which would freeze the window if it was done all at once.
+const DRAWS_PER_FRAME: u32 = 1_000_000;
+
+/// A simulation that's still running, with the pulls and settings it was started for.
+struct RunningSimulation {
+    pulls: u32,
+    settings: OddsSettings,
+    tally: OddsTally,
+    rng: Rng,
+}
+
+/// The last simulation run along with the pulls and settings it was run for.
+#[derive(Default)]
+pub struct OddsSimulation {
+    result: Option<(u32, OddsSettings, Odds)>,
+    running: Option<RunningSimulation>,
+    runs: u64,
+}
+
+/// Whether `simulated` was run for the same pulls and rates as `pulls` and `settings`.
+fn simulated_for(simulated: (u32, &OddsSettings), pulls: u32, settings: &OddsSettings) -> bool {
+    let (simulated_pulls, simulated_settings) = simulated;
+    simulated_pulls == pulls
+        && simulated_settings.doubled_rates == settings.doubled_rates
+        && simulated_settings.rate_up_percent == settings.rate_up_percent
+}
+
+pub fn place_odds(
+    settings: &mut OddsSettings,
+    simulation: &mut OddsSimulation,
+    pulls: u32,
+    ui: &mut Ui,
+) {
+    ui.heading("What Are My Odds?");
+    ui.add_space(5.);
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut settings.doubled_rates, false, "Normal (3%)");
+        ui.radio_value(&mut settings.doubled_rates, true, "Gala (6%)");
+    });
+    ui.add_space(5.);
+    let max_rate_up_percent = settings.ssr_rate() as f32 * 100.;
+    ui.horizontal(|ui| {
+        ui.label("Rate-Up SSR: ");
+        ui.add(
+            egui::DragValue::new(&mut settings.rate_up_percent)
+                .speed(0.01)
+                .clamp_range(0.0..=max_rate_up_percent)
+                .suffix("%"),
+        );
+    });
+    ui.add_space(5.);
+
+    let odds = calculate_odds(pulls, settings.ssr_rate(), settings.rate_up_rate());
+    let simulated = match &simulation.result {
+        Some((simulated_pulls, simulated_settings, simulated))
+            if settings.monte_carlo
+                && simulated_for((*simulated_pulls, simulated_settings), pulls, settings) =>
+        {
+            Some(*simulated)
+        }
+        _ => None,
+    };
+    let with_simulated = |value: String, simulated_value: Option<String>| match simulated_value {
+        Some(simulated_value) => format!("{} (simulated {})", value, simulated_value),
+        None => value,
+    };
+
+    ui.label(with_simulated(
+        format!(
+            "Rate-Up SSR before spark: {}",
+            format_rate(odds.rate_up_before_spark as f32)
+        ),
+        simulated.map(|x| format_rate(x.rate_up_before_spark as f32)),
+    ));
+    if pulls >= SPARK_PULLS {
+        ui.label("You have enough pulls to spark it!");
+    }
+    ui.label(with_simulated(
+        format!("At least one SSR: {}", format_rate(odds.any_ssr as f32)),
+        simulated.map(|x| format_rate(x.any_ssr as f32)),
+    ));
+    ui.label(with_simulated(
+        format!("Expected SSRs: {:.2}", odds.expected_ssrs),
+        simulated.map(|x| format!("{:.2}", x.expected_ssrs)),
+    ));
+    ui.add_space(5.);
+
+    ui.checkbox(&mut settings.monte_carlo, "Cross-check with a simulation");
+    if !settings.monte_carlo {
+        simulation.running = None;
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.label("Runs: ");
+        ui.add(
+            egui::DragValue::new(&mut settings.trials)
+                .speed(100)
+                .clamp_range(100..=100_000),
+        );
+        if ui.button("Simulate").clicked() {
+            simulation.runs += 1;
+            simulation.running = Some(RunningSimulation {
+                pulls,
+                settings: settings.clone(),
+                tally: OddsTally::default(),
+                rng: Rng::new(time_seed().wrapping_add(simulation.runs)),
+            });
+        }
+    });
+
+    // Changing the pulls or rates makes a running simulation pointless
+    let is_stale = match &simulation.running {
+        Some(running) => !simulated_for((running.pulls, &running.settings), pulls, settings),
+        None => false,
+    };
+    if is_stale {
+        simulation.running = None;
+    }
+    if let Some(running) = &mut simulation.running {
+        let trials = running.settings.trials;
+        let chunk = (DRAWS_PER_FRAME / pulls.max(1))
+            .max(1)
+            .min(trials - running.tally.trials);
+        running.tally.run(
+            pulls,
+            running.settings.ssr_rate(),
+            running.settings.rate_up_rate(),
+            chunk,
+            &mut running.rng,
+        );
+        if running.tally.trials >= trials {
+            simulation.result = Some((pulls, running.settings.clone(), running.tally.odds()));
+            simulation.running = None;
+        } else {
+            ui.add(
+                egui::ProgressBar::new(running.tally.trials as f32 / trials as f32)
+                    .text("Simulating..."),
+            );
+            ui.ctx().request_repaint();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATES: [(f64, f64); 3] = [(0.03, 0.003), (0.06, 0.006), (0.03, 0.03)];
+
+    #[test]
+    fn calculate_odds_at_3_percent() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-4;
+        let none = calculate_odds(0, 0.03, 0.003);
+        assert_eq!((none.rate_up_before_spark, none.any_ssr), (0., 0.));
+        let single = calculate_odds(1, 0.03, 0.003);
+        assert!(close(single.any_ssr, 0.03));
+        assert!(close(single.rate_up_before_spark, 0.003));
+        // 1 - 0.97^10 and 1 - 0.997^10
+        let ten = calculate_odds(10, 0.03, 0.003);
+        assert!(close(ten.any_ssr, 0.2626));
+        assert!(close(ten.rate_up_before_spark, 0.0296));
+        assert!(close(ten.expected_ssrs, 0.3));
+        // 1 - 0.97^300 and 1 - 0.997^300
+        let spark = calculate_odds(300, 0.03, 0.003);
+        assert!(close(spark.any_ssr, 0.9999));
+        assert!(close(spark.rate_up_before_spark, 0.5940));
+        assert!(close(spark.expected_ssrs, 9.));
+    }
+
+    #[test]
+    fn calculate_odds_stops_counting_the_rate_up_at_a_spark() {
+        let spark = calculate_odds(SPARK_PULLS, 0.03, 0.003);
+        let past_spark = calculate_odds(SPARK_PULLS + 100, 0.03, 0.003);
+        assert_eq!(spark.rate_up_before_spark, past_spark.rate_up_before_spark);
+        assert!(past_spark.any_ssr > spark.any_ssr);
+    }
+
+    #[test]
+    fn simulate_odds_converges_to_calculate_odds() {
+        let mut rng = Rng::new(42);
+        for (ssr_rate, rate_up_rate) in RATES {
+            for pulls in [10, 100, 350] {
+                let odds = calculate_odds(pulls, ssr_rate, rate_up_rate);
+                let simulated = simulate_odds(pulls, ssr_rate, rate_up_rate, 20_000, &mut rng);
+                assert!((simulated.rate_up_before_spark - odds.rate_up_before_spark).abs() < 0.015);
+                assert!((simulated.any_ssr - odds.any_ssr).abs() < 0.015);
+                assert!(
+                    (simulated.expected_ssrs - odds.expected_ssrs).abs()
+                        < odds.expected_ssrs * 0.03
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn simulating_in_chunks_matches_simulating_at_once() {
+        let at_once = simulate_odds(50, 0.03, 0.003, 1_000, &mut Rng::new(7));
+        let mut rng = Rng::new(7);
+        let mut tally = OddsTally::default();
+        for _ in 0..4 {
+            tally.run(50, 0.03, 0.003, 250, &mut rng);
+        }
+        assert_eq!(tally.trials, 1_000);
+        assert_eq!(tally.odds(), at_once);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::theme::Theme;
+use crate::{place_filter_checkbox, ChestType, Item, ItemDrop, Raid};
+use eframe::egui::{self, Color32, RichText, Ui};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Color of a highlighted drop. The theme colors follow whichever theme is in use.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum HighlightColor {
+    GoldBar,
+    EternitySand,
+    Earring,
+    /// sRGB
+    Custom([u8; 3]),
+}
+
+impl HighlightColor {
+    pub const THEME_COLORS: [HighlightColor; 3] = [
+        HighlightColor::GoldBar,
+        HighlightColor::EternitySand,
+        HighlightColor::Earring,
+    ];
+
+    pub fn color(&self, theme: &Theme) -> Color32 {
+        match *self {
+            HighlightColor::GoldBar => theme.gold_bar_color(),
+            HighlightColor::EternitySand => theme.eternity_sand_color(),
+            HighlightColor::Earring => theme.earring_color(),
+            HighlightColor::Custom([r, g, b]) => Color32::from_rgb(r, g, b),
+        }
+    }
+}
+
+impl fmt::Display for HighlightColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            HighlightColor::GoldBar => write!(f, "Theme: Gold Bars"),
+            HighlightColor::EternitySand => write!(f, "Theme: Eternity Sand"),
+            HighlightColor::Earring => write!(f, "Theme: Earrings"),
+            HighlightColor::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Highlights drops of `items` from `raids` in `chests`. Empty lists match anything, like the
+/// Filtered Export's.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct HighlightRule {
+    pub name: String,
+    pub enabled: bool,
+    pub items: Vec<Item>,
+    pub raids: Vec<Raid>,
+    pub chests: Vec<ChestType>,
+    pub color: HighlightColor,
+    pub bold: bool,
+    /// Text put in front of the drop, e.g. "★".
+    pub icon: String,
+}
+
+impl HighlightRule {
+    pub fn new(name: &str, items: Vec<Item>, color: HighlightColor) -> Self {
+        Self {
+            name: name.to_string(),
+            enabled: true,
+            items,
+            raids: vec![],
+            chests: vec![],
+            color,
+            bold: false,
+            icon: String::new(),
+        }
+    }
+
+    pub fn matches(&self, item: Item, raid: Raid, chest: ChestType) -> bool {
+        self.enabled
+            && (self.items.is_empty() || self.items.contains(&item))
+            && (self.raids.is_empty() || self.raids.contains(&raid))
+            && (self.chests.is_empty() || self.chests.contains(&chest))
+    }
+
+    /// `text` in the rule's color and weight, after its icon.
+    pub fn style(&self, text: &str, theme: &Theme) -> RichText {
+        let text = if self.icon.trim().is_empty() {
+            RichText::new(text)
+        } else {
+            RichText::new(format!("{} {}", self.icon.trim(), text))
+        };
+        let text = text.color(self.color.color(theme));
+        if self.bold {
+            text.strong()
+        } else {
+            text
+        }
+    }
+}
+
+/// Gold bars, Eternity Sand and earrings in the theme's colors.
+pub fn default_rules() -> Vec<HighlightRule> {
+    vec![
+        HighlightRule::new("Gold Bars", vec![Item::GoldBrick], HighlightColor::GoldBar),
+        HighlightRule::new(
+            "Eternity Sand",
+            vec![Item::EternitySand],
+            HighlightColor::EternitySand,
+        ),
+        HighlightRule::new(
+            "Earrings",
+            Item::ALL.into_iter().filter(|x| x.is_earring()).collect(),
+            HighlightColor::Earring,
+        ),
+    ]
+}
+
+/// The first rule that highlights `drop`. Rules higher in the list win.
+pub fn matching_rule<'a>(rules: &'a [HighlightRule], drop: &ItemDrop) -> Option<&'a HighlightRule> {
+    rules
+        .iter()
+        .find(|x| x.matches(drop.item, drop.raid, drop.chest))
+}
+
+/// Name of the first rule highlighting `item` from `raid` in `chest`, or an empty string, for
+/// exports.
+pub fn highlight_name(rules: &[HighlightRule], item: Item, raid: Raid, chest: ChestType) -> String {
+    rules
+        .iter()
+        .find(|x| x.matches(item, raid, chest))
+        .map(|x| x.name.clone())
+        .unwrap_or_default()
+}
+
+/// State of the Highlight Rules window.
+#[derive(Default)]
+pub struct HighlightEditor {
+    pub open: bool,
+}
+
+/// Editing, reordering and adding highlight rules.
+pub fn place_highlight_editor(rules: &mut Vec<HighlightRule>, theme: &Theme, ui: &mut Ui) {
+    ui.label("Drops take the look of the first rule they match. Empty lists match anything.");
+    ui.add_space(5.);
+    let mut move_up = None;
+    let mut remove = None;
+    let rule_count = rules.len();
+    for (index, rule) in rules.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut rule.enabled, "");
+            ui.text_edit_singleline(&mut rule.name);
+            ui.label(rule.style("Preview", theme));
+        });
+        ui.horizontal(|ui| {
+            ui.menu_button(format!("Items ({})", rule.items.len()), |ui| {
+                for item in Item::ALL {
+                    place_filter_checkbox(item, item.to_string(), &mut rule.items, ui);
+                }
+            });
+            ui.menu_button(format!("Raids ({})", rule.raids.len()), |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(300.)
+                    .show(ui, |ui| {
+                        for raid in Raid::ALL {
+                            place_filter_checkbox(raid, raid.to_string(), &mut rule.raids, ui);
+                        }
+                    });
+            });
+            ui.menu_button(format!("Chests ({})", rule.chests.len()), |ui| {
+                for chest in ChestType::ALL {
+                    if chest != ChestType::None {
+                        place_filter_checkbox(chest, chest.to_string(), &mut rule.chests, ui);
+                    }
+                }
+            });
+        });
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source(("highlight_color", index))
+                .selected_text(rule.color.to_string())
+                .show_ui(ui, |ui| {
+                    for color in HighlightColor::THEME_COLORS {
+                        ui.selectable_value(&mut rule.color, color, color.to_string());
+                    }
+                    if ui
+                        .selectable_label(matches!(rule.color, HighlightColor::Custom(_)), "Custom")
+                        .clicked()
+                    {
+                        let [r, g, b, _] = rule.color.color(theme).to_array();
+                        rule.color = HighlightColor::Custom([r, g, b]);
+                    }
+                });
+            if let HighlightColor::Custom(srgb) = &mut rule.color {
+                ui.color_edit_button_srgb(srgb);
+            }
+            ui.checkbox(&mut rule.bold, "Bold");
+            ui.label("Icon");
+            ui.add(egui::TextEdit::singleline(&mut rule.icon).desired_width(30.));
+            if ui.add_enabled(index > 0, egui::Button::new("Up")).clicked() {
+                move_up = Some(index);
+            }
+            if ui
+                .add_enabled(index + 1 < rule_count, egui::Button::new("Down"))
+                .clicked()
+            {
+                move_up = Some(index + 1);
+            }
+            if ui.button("Delete").clicked() {
+                remove = Some(index);
+            }
+        });
+        ui.separator();
+    }
+    if let Some(index) = move_up {
+        rules.swap(index - 1, index);
+    }
+    if let Some(index) = remove {
+        rules.remove(index);
+    }
+    ui.horizontal(|ui| {
+        if ui.button("Add Rule").clicked() {
+            rules.push(HighlightRule::new(
+                "New Rule",
+                vec![],
+                HighlightColor::Custom([255, 255, 255]),
+            ));
+        }
+        if ui.button("Reset to Defaults").clicked() {
+            *rules = default_rules();
+        }
+    });
+}
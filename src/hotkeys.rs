@@ -0,0 +1,464 @@
+use crate::{tab_buttons, ChestType, DorothyConfig, Item, Raid};
+use eframe::egui::{self, Align2, FontId, InputState, Key, Rect, Ui, Vec2};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Keys a hotkey can be bound to, by the name egui gives them.
+const KEYS: [Key; 51] = [
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::P,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+    Key::Num0,
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+    Key::ArrowDown,
+    Key::ArrowLeft,
+    Key::ArrowRight,
+    Key::ArrowUp,
+    Key::Escape,
+    Key::Tab,
+    Key::Backspace,
+    Key::Enter,
+    Key::Space,
+    Key::Insert,
+    Key::Delete,
+    Key::Home,
+    Key::End,
+    Key::PageUp,
+    Key::PageDown,
+];
+
+pub fn key_name(key: Key) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Num").unwrap_or(&name).to_string()
+}
+
+pub fn key_from_name(name: &str) -> Option<Key> {
+    KEYS.iter().copied().find(|key| key_name(*key) == name)
+}
+
+/// Keys handed out to the current tab's items in Quick Entry Mode, in order.
+const QUICK_ENTRY_KEYS: [Key; 36] = [
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+    Key::Num0,
+    Key::Q,
+    Key::W,
+    Key::E,
+    Key::R,
+    Key::T,
+    Key::Y,
+    Key::U,
+    Key::I,
+    Key::O,
+    Key::P,
+    Key::A,
+    Key::S,
+    Key::D,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::Z,
+    Key::X,
+    Key::C,
+    Key::V,
+    Key::B,
+    Key::N,
+    Key::M,
+];
+
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum HotkeyAction {
+    ToggleRightPanel,
+    ToggleLeftPanel,
+    ToggleAlwaysOnTop,
+    ToggleDarkMode,
+    Export,
+    ToggleCenterPanelFeatures,
+    LogNoDrop,
+    Undo,
+    ToggleCompactOverlay,
+    /// Logs the drops ticked while logging by kill as one kill.
+    LogKill,
+    /// Logs the item for the current tab's raid, the same as clicking its button.
+    LogItem(Item),
+}
+
+impl HotkeyAction {
+    pub fn all() -> Vec<HotkeyAction> {
+        let mut actions = vec![
+            HotkeyAction::ToggleRightPanel,
+            HotkeyAction::ToggleLeftPanel,
+            HotkeyAction::ToggleAlwaysOnTop,
+            HotkeyAction::ToggleDarkMode,
+            HotkeyAction::Export,
+            HotkeyAction::ToggleCenterPanelFeatures,
+            HotkeyAction::LogNoDrop,
+            HotkeyAction::Undo,
+            HotkeyAction::ToggleCompactOverlay,
+            HotkeyAction::LogKill,
+        ];
+        for item in Item::ALL {
+            if item != Item::NoDrop {
+                actions.push(HotkeyAction::LogItem(item));
+            }
+        }
+        actions
+    }
+}
+
+impl fmt::Display for HotkeyAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            HotkeyAction::ToggleRightPanel => write!(f, "Toggle Right Panel"),
+            HotkeyAction::ToggleLeftPanel => write!(f, "Toggle Left Panel"),
+            HotkeyAction::ToggleAlwaysOnTop => write!(f, "Toggle Always On Top"),
+            HotkeyAction::ToggleDarkMode => write!(f, "Toggle Dark Mode"),
+            HotkeyAction::Export => write!(f, "Export"),
+            HotkeyAction::ToggleCenterPanelFeatures => write!(f, "Toggle Center Panel Features"),
+            HotkeyAction::LogNoDrop => write!(f, "Log No Drop"),
+            HotkeyAction::Undo => write!(f, "Undo Last Drop"),
+            HotkeyAction::ToggleCompactOverlay => write!(f, "Toggle Compact Overlay"),
+            HotkeyAction::LogKill => write!(f, "Log Kill"),
+            HotkeyAction::LogItem(item) => write!(f, "Log {}", item),
+        }
+    }
+}
+
+/// A key along with the modifiers that have to be held for it. Ctrl is Cmd on macOS.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Hotkey {
+    pub key: String,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub alt: bool,
+}
+
+impl Hotkey {
+    pub fn shift(key: Key) -> Self {
+        Self {
+            key: key_name(key),
+            shift: true,
+            ctrl: false,
+            alt: false,
+        }
+    }
+
+    pub fn ctrl(key: Key) -> Self {
+        Self {
+            key: key_name(key),
+            shift: false,
+            ctrl: true,
+            alt: false,
+        }
+    }
+
+    /// Whether the hotkey was pressed this frame with exactly its modifiers held.
+    pub fn pressed(&self, input: &InputState) -> bool {
+        match key_from_name(&self.key) {
+            Some(key) => {
+                input.key_pressed(key)
+                    && input.modifiers.shift == self.shift
+                    && input.modifiers.command == self.ctrl
+                    && input.modifiers.alt == self.alt
+            }
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keybindings {
+    /// Actions without a hotkey aren't listed.
+    pub bindings: Vec<(HotkeyAction, Hotkey)>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            bindings: vec![
+                (HotkeyAction::ToggleRightPanel, Hotkey::shift(Key::R)),
+                (HotkeyAction::ToggleLeftPanel, Hotkey::shift(Key::D)),
+                (HotkeyAction::ToggleAlwaysOnTop, Hotkey::shift(Key::A)),
+                (HotkeyAction::ToggleDarkMode, Hotkey::shift(Key::L)),
+                (HotkeyAction::Export, Hotkey::shift(Key::E)),
+                (
+                    HotkeyAction::ToggleCenterPanelFeatures,
+                    Hotkey::shift(Key::S),
+                ),
+                (HotkeyAction::LogNoDrop, Hotkey::shift(Key::N)),
+                (HotkeyAction::Undo, Hotkey::ctrl(Key::Z)),
+            ],
+        }
+    }
+}
+
+impl Keybindings {
+    pub fn hotkey(&self, action: HotkeyAction) -> Option<&Hotkey> {
+        self.bindings
+            .iter()
+            .find(|(bound_action, _)| *bound_action == action)
+            .map(|(_, hotkey)| hotkey)
+    }
+
+    /// Another action already bound to `hotkey`, if any.
+    pub fn conflict(&self, action: HotkeyAction, hotkey: &Hotkey) -> Option<HotkeyAction> {
+        self.bindings
+            .iter()
+            .find(|(bound_action, bound_hotkey)| *bound_action != action && bound_hotkey == hotkey)
+            .map(|(bound_action, _)| *bound_action)
+    }
+
+    /// Binds `hotkey` to `action` unless another action already uses it, which is returned instead.
+    pub fn bind(&mut self, action: HotkeyAction, hotkey: Hotkey) -> Result<(), HotkeyAction> {
+        if let Some(conflict) = self.conflict(action, &hotkey) {
+            return Err(conflict);
+        }
+        self.unbind(action);
+        self.bindings.push((action, hotkey));
+        Ok(())
+    }
+
+    pub fn unbind(&mut self, action: HotkeyAction) {
+        self.bindings
+            .retain(|(bound_action, _)| *bound_action != action);
+    }
+
+    /// Actions whose hotkey was pressed this frame. A hotkey bound to several actions (only
+    /// possible by editing the settings by hand) only runs the first of them.
+    pub fn pressed(&self, input: &InputState) -> Vec<HotkeyAction> {
+        let mut pressed: Vec<&Hotkey> = vec![];
+        let mut actions = vec![];
+        for (action, hotkey) in &self.bindings {
+            if hotkey.pressed(input) && !pressed.contains(&hotkey) {
+                pressed.push(hotkey);
+                actions.push(*action);
+            }
+        }
+        actions
+    }
+}
+
+/// Quick Entry Mode keys for `count` items. A key is skipped when a hotkey already uses it,
+/// with or without Shift, since Shift+key removes a drop.
+pub fn quick_entry_keys(keybindings: &Keybindings, count: usize) -> Vec<Key> {
+    QUICK_ENTRY_KEYS
+        .iter()
+        .copied()
+        .filter(|key| {
+            !keybindings
+                .bindings
+                .iter()
+                .any(|(_, hotkey)| hotkey.key == key_name(*key) && !hotkey.ctrl && !hotkey.alt)
+        })
+        .take(count)
+        .collect()
+}
+
+/// Current tab's items paired with their Quick Entry Mode keys. Items past the last key get none.
+pub fn quick_entry_buttons(
+    settings: &DorothyConfig,
+    selected_raid: Raid,
+) -> Vec<((Item, Raid, ChestType), Key)> {
+    let buttons = tab_buttons(settings, selected_raid);
+    let keys = quick_entry_keys(&settings.hotkeys, buttons.len());
+    buttons.into_iter().zip(keys).collect()
+}
+
+/// Draws a Quick Entry Mode key in the top left corner of an item's button.
+pub fn paint_key_badge(ui: &Ui, rect: Rect, key: Key) {
+    let badge = Rect::from_min_size(rect.left_top(), Vec2::splat(14.));
+    let visuals = ui.visuals();
+    ui.painter()
+        .rect_filled(badge, 3., visuals.selection.bg_fill);
+    ui.painter().text(
+        badge.center(),
+        Align2::CENTER_CENTER,
+        key_name(key),
+        FontId::proportional(11.),
+        visuals.strong_text_color(),
+    );
+}
+
+/// State of the "Hotkeys" window.
+#[derive(Default)]
+pub struct HotkeyEditor {
+    pub open: bool,
+    /// Action waiting for the next key press to become its hotkey.
+    recording: Option<HotkeyAction>,
+    /// Time of the frame the last recording ended in. The key that ended it is still pressed in
+    /// that frame and shouldn't run what it's bound to.
+    recording_ended: Option<f64>,
+    message: Option<String>,
+}
+
+impl HotkeyEditor {
+    /// Hotkeys shouldn't run while the key meant for a new hotkey is being pressed, including in
+    /// the frame at `frame_time` if that's when it was pressed.
+    pub fn is_recording(&self, frame_time: f64) -> bool {
+        self.recording.is_some() || self.recording_ended == Some(frame_time)
+    }
+}
+
+pub fn place_hotkey_editor(editor: &mut HotkeyEditor, keybindings: &mut Keybindings, ui: &mut Ui) {
+    if let Some(action) = editor.recording {
+        let pressed = ui.input().events.iter().find_map(|event| match event {
+            egui::Event::Key {
+                key,
+                pressed: true,
+                modifiers,
+            } => Some((*key, *modifiers)),
+            _ => None,
+        });
+        if let Some((key, modifiers)) = pressed {
+            editor.recording = None;
+            editor.recording_ended = Some(ui.input().time);
+            if key == Key::Escape && !modifiers.any() {
+                editor.message = None;
+            } else {
+                let hotkey = Hotkey {
+                    key: key_name(key),
+                    shift: modifiers.shift,
+                    ctrl: modifiers.command,
+                    alt: modifiers.alt,
+                };
+                editor.message = match keybindings.bind(action, hotkey.clone()) {
+                    Ok(()) => None,
+                    Err(conflict) => Some(format!("{} is already used by {}", hotkey, conflict)),
+                };
+            }
+        }
+    }
+
+    ui.label("Click Set and press the keys to use. Escape cancels.");
+    if let Some(message) = &editor.message {
+        ui.colored_label(egui::Color32::from_rgb(255, 90, 90), message);
+    }
+    ui.add_space(5.);
+    egui::Grid::new("hotkey_grid")
+        .striped(true)
+        .spacing((15., 5.))
+        .show(ui, |ui| {
+            for action in HotkeyAction::all() {
+                ui.label(action.to_string());
+                if editor.recording == Some(action) {
+                    ui.label("Press a key...");
+                } else {
+                    match keybindings.hotkey(action) {
+                        Some(hotkey) => match keybindings.conflict(action, hotkey) {
+                            Some(conflict) => {
+                                ui.colored_label(
+                                    egui::Color32::from_rgb(255, 90, 90),
+                                    format!("{} (also {})", hotkey, conflict),
+                                );
+                            }
+                            None => {
+                                ui.label(hotkey.to_string());
+                            }
+                        },
+                        None => {
+                            ui.label("");
+                        }
+                    }
+                }
+                if ui.button("Set").clicked() {
+                    editor.recording = Some(action);
+                    editor.message = None;
+                }
+                if ui
+                    .add_enabled(
+                        keybindings.hotkey(action).is_some(),
+                        egui::Button::new("Clear"),
+                    )
+                    .clicked()
+                {
+                    keybindings.unbind(action);
+                }
+                ui.end_row();
+            }
+        });
+    ui.add_space(5.);
+    if ui.button("Reset to Defaults").clicked() {
+        *keybindings = Keybindings::default();
+        editor.recording = None;
+        editor.message = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_key_ending_a_recording_runs_nothing_in_its_frame() {
+        let mut editor = HotkeyEditor {
+            recording: Some(HotkeyAction::Export),
+            ..Default::default()
+        };
+        assert!(editor.is_recording(1.));
+        editor.recording = None;
+        editor.recording_ended = Some(1.);
+        assert!(editor.is_recording(1.));
+        assert!(!editor.is_recording(1.1));
+        assert!(!HotkeyEditor::default().is_recording(0.));
+    }
+}
@@ -0,0 +1,547 @@
+use crate::{ChestType, Item, ItemDrop, Raid};
+use chrono::{NaiveDate, NaiveDateTime};
+use eframe::egui::{self, Ui};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+/// What a column of another tracker's spreadsheet holds.
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum ImportField {
+    #[default]
+    Ignore,
+    Date,
+    Raid,
+    Item,
+    Chest,
+    Honors,
+}
+
+impl ImportField {
+    pub const ALL: [ImportField; 6] = [
+        ImportField::Ignore,
+        ImportField::Date,
+        ImportField::Raid,
+        ImportField::Item,
+        ImportField::Chest,
+        ImportField::Honors,
+    ];
+}
+
+impl fmt::Display for ImportField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ImportField::Ignore => write!(f, "Ignore"),
+            ImportField::Date => write!(f, "Date"),
+            ImportField::Raid => write!(f, "Raid"),
+            ImportField::Item => write!(f, "Item"),
+            ImportField::Chest => write!(f, "Chest"),
+            ImportField::Honors => write!(f, "Honors"),
+        }
+    }
+}
+
+/// Translates a cell's text into one of Dorothy's values. A `target` of `None` skips the row.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct ValueMapping<T> {
+    pub source: String,
+    pub target: Option<T>,
+}
+
+/// A spreadsheet as it was read, before any mapping is applied.
+#[derive(Clone, Default, Debug)]
+pub struct RawCsv {
+    pub records: Vec<Vec<String>>,
+}
+
+impl RawCsv {
+    pub fn read(bytes: &[u8], delimiter: char) -> Result<Self, Box<dyn Error>> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(delimiter as u8)
+            .from_reader(bytes);
+        let mut records = vec![];
+        for record in rdr.records() {
+            records.push(record?.iter().map(|x| x.trim().to_string()).collect());
+        }
+        Ok(Self { records })
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.records
+            .iter()
+            .map(|x| x.len())
+            .max()
+            .unwrap_or_default()
+    }
+
+    pub fn headers(&self, has_headers: bool) -> Vec<String> {
+        (0..self.column_count())
+            .map(|i| match self.records.first().and_then(|x| x.get(i)) {
+                Some(header) if has_headers && !header.is_empty() => header.clone(),
+                _ => format!("Column {}", i + 1),
+            })
+            .collect()
+    }
+
+    pub fn rows(&self, has_headers: bool) -> &[Vec<String>] {
+        if has_headers && !self.records.is_empty() {
+            &self.records[1..]
+        } else {
+            &self.records
+        }
+    }
+
+    /// Every distinct value found in a column, in the order they first appear.
+    pub fn distinct_values(&self, column: usize, has_headers: bool) -> Vec<String> {
+        let mut values: Vec<String> = vec![];
+        for row in self.rows(has_headers) {
+            if let Some(value) = row.get(column) {
+                if !values.contains(value) {
+                    values.push(value.clone());
+                }
+            }
+        }
+        values
+    }
+}
+
+/// How another tracker's columns and values turn into drops. Saved as a preset for reuse.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct ImportMapping {
+    pub name: String,
+    pub delimiter: char,
+    pub has_headers: bool,
+    pub columns: Vec<ImportField>,
+    /// chrono format of the date column. Left empty the date is kept exactly as written.
+    pub date_format: String,
+    pub raid_values: Vec<ValueMapping<Raid>>,
+    pub item_values: Vec<ValueMapping<Item>>,
+    pub chest_values: Vec<ValueMapping<ChestType>>,
+    /// Used when no column is mapped to a raid
+    pub default_raid: Raid,
+    /// Used when no column is mapped to a chest
+    pub default_chest: ChestType,
+}
+
+impl Default for ImportMapping {
+    fn default() -> Self {
+        Self {
+            name: "".to_string(),
+            delimiter: ',',
+            has_headers: true,
+            columns: vec![],
+            date_format: "".to_string(),
+            raid_values: vec![],
+            item_values: vec![],
+            chest_values: vec![],
+            default_raid: Raid::None,
+            default_chest: ChestType::Blue,
+        }
+    }
+}
+
+/// Drops an import would add along with the rows it couldn't understand.
+#[derive(Clone, Default, Debug)]
+pub struct ImportPreview {
+    pub drops: Vec<ItemDrop>,
+    pub skipped: Vec<String>,
+}
+
+impl ImportMapping {
+    pub fn column_for(&self, field: ImportField) -> Option<usize> {
+        self.columns.iter().position(|x| *x == field)
+    }
+
+    /// Makes sure every column and every value found in `csv` has a mapping,
+    /// guessing the ones that match Dorothy's own names.
+    pub fn refresh(&mut self, csv: &RawCsv) {
+        self.columns.resize(csv.column_count(), ImportField::Ignore);
+        if let Some(column) = self.column_for(ImportField::Raid) {
+            refresh_values(
+                &mut self.raid_values,
+                csv.distinct_values(column, self.has_headers),
+                &Raid::ALL,
+            );
+        }
+        if let Some(column) = self.column_for(ImportField::Item) {
+            refresh_values(
+                &mut self.item_values,
+                csv.distinct_values(column, self.has_headers),
+                &Item::ALL,
+            );
+        }
+        if let Some(column) = self.column_for(ImportField::Chest) {
+            refresh_values(
+                &mut self.chest_values,
+                csv.distinct_values(column, self.has_headers),
+                &ChestType::ALL,
+            );
+        }
+    }
+
+    pub fn apply(&self, csv: &RawCsv) -> ImportPreview {
+        let mut preview = ImportPreview::default();
+        let first_row = if self.has_headers { 2 } else { 1 };
+        for (pos, row) in csv.rows(self.has_headers).iter().enumerate() {
+            match self.apply_to_row(row, pos as u32) {
+                Ok(drop) => preview.drops.push(drop),
+                Err(reason) => preview
+                    .skipped
+                    .push(format!("Row {}: {}", pos + first_row, reason)),
+            }
+        }
+        preview
+    }
+
+    fn apply_to_row(&self, row: &[String], drop_id: u32) -> Result<ItemDrop, String> {
+        let cell = |field: ImportField| {
+            self.column_for(field)
+                .map(|column| row.get(column).cloned().unwrap_or_default())
+        };
+
+        let raid = match cell(ImportField::Raid) {
+            Some(value) => lookup(&self.raid_values, &value)
+                .ok_or(format!("\"{}\" isn't mapped to a raid", value))?,
+            None => self.default_raid,
+        };
+        let item = match cell(ImportField::Item) {
+            Some(value) => lookup(&self.item_values, &value)
+                .ok_or(format!("\"{}\" isn't mapped to an item", value))?,
+            None => return Err("No column is mapped to an item".to_string()),
+        };
+        let chest = match cell(ImportField::Chest) {
+            Some(value) => lookup(&self.chest_values, &value)
+                .ok_or(format!("\"{}\" isn't mapped to a chest", value))?,
+            None if item == Item::NoDrop => ChestType::None,
+            None => self.default_chest,
+        };
+        let date_obtained = match cell(ImportField::Date) {
+            Some(value) => self.parse_date(&value)?,
+            None => "".to_string(),
+        };
+
+        Ok(ItemDrop::new(
+            drop_id,
+            date_obtained,
+            raid,
+            item,
+            chest,
+            Some(cell(ImportField::Honors).unwrap_or_default()),
+        ))
+    }
+
+    /// Rewrites a date into the same `%Y-%m-%d %H:%M:%S` format Dorothy logs drops with.
+    fn parse_date(&self, value: &str) -> Result<String, String> {
+        let date_format = self.date_format.trim();
+        if date_format.is_empty() {
+            return Ok(value.to_string());
+        }
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(value, date_format) {
+            return Ok(datetime.format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+        match NaiveDate::parse_from_str(value, date_format) {
+            Ok(date) => Ok(date.format("%Y-%m-%d 00:00:00").to_string()),
+            Err(_) => Err(format!("\"{}\" doesn't match {}", value, date_format)),
+        }
+    }
+}
+
+fn lookup<T: Copy>(mappings: &[ValueMapping<T>], value: &str) -> Option<T> {
+    mappings
+        .iter()
+        .find(|x| x.source == value)
+        .and_then(|x| x.target)
+}
+
+fn refresh_values<T: Copy + fmt::Display + fmt::Debug>(
+    mappings: &mut Vec<ValueMapping<T>>,
+    values: Vec<String>,
+    options: &[T],
+) {
+    for value in values {
+        if !mappings.iter().any(|x| x.source == value) {
+            mappings.push(ValueMapping {
+                target: guess_value(&value, options),
+                source: value,
+            });
+        }
+    }
+}
+
+/// Matches a value against Dorothy's names ignoring case, spaces and punctuation,
+/// so "coronation ring" and "CoronationRing" both find `Item::CoronationRing`.
+pub fn guess_value<T: Copy + fmt::Display + fmt::Debug>(value: &str, options: &[T]) -> Option<T> {
+    let simplify = |text: &str| {
+        text.chars()
+            .filter(|x| x.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let value = simplify(value);
+    if value.is_empty() {
+        return None;
+    }
+    options.iter().copied().find(|option| {
+        simplify(&option.to_string()) == value || simplify(&format!("{:?}", option)) == value
+    })
+}
+
+/// State of the "Import From Another Tracker" window.
+#[derive(Default)]
+pub struct TrackerImport {
+    pub open: bool,
+    pub file_path: String,
+    pub file_name: String,
+    pub contents: Vec<u8>,
+    pub csv: RawCsv,
+    pub mapping: ImportMapping,
+    pub error: Option<String>,
+    /// The preview and the mapping it was made with, so it's only made again when the mapping
+    /// changes.
+    preview: Option<(ImportMapping, ImportPreview)>,
+    #[cfg(target_arch = "wasm32")]
+    pub pending: crate::export::PendingImport,
+}
+
+impl TrackerImport {
+    pub fn load(&mut self, file_name: String, contents: Vec<u8>) {
+        self.file_name = file_name;
+        self.contents = contents;
+        self.reread();
+    }
+
+    /// Parses the loaded file again, e.g. after the delimiter changed.
+    pub fn reread(&mut self) {
+        self.preview = None;
+        match RawCsv::read(&self.contents, self.mapping.delimiter) {
+            Ok(csv) => {
+                self.csv = csv;
+                self.mapping.refresh(&self.csv);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Dorothy couldn't read {}: {}", self.file_name, e)),
+        }
+    }
+
+    /// What importing the file with the current mapping would add.
+    pub fn preview(&mut self) -> &ImportPreview {
+        if !matches!(&self.preview, Some((mapping, _)) if *mapping == self.mapping) {
+            self.preview = Some((self.mapping.clone(), self.mapping.apply(&self.csv)));
+        }
+        &self.preview.as_ref().unwrap().1
+    }
+}
+
+fn place_value_mappings<T: Copy + PartialEq + fmt::Display>(
+    id: &str,
+    mappings: &mut [ValueMapping<T>],
+    options: &[T],
+    ui: &mut Ui,
+) {
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+        for mapping in mappings.iter_mut() {
+            ui.label(&mapping.source);
+            let selected_text = match mapping.target {
+                Some(target) => target.to_string(),
+                None => "Skip row".to_string(),
+            };
+            egui::ComboBox::from_id_source((id, &mapping.source))
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut mapping.target, None, "Skip row");
+                    for option in options {
+                        ui.selectable_value(&mut mapping.target, Some(*option), option.to_string());
+                    }
+                });
+            ui.end_row();
+        }
+    });
+}
+
+/// Draws the mapping dialog. Returns the drops to add once the user confirms the import.
+pub fn place_import_mapping(
+    state: &mut TrackerImport,
+    presets: &mut Vec<ImportMapping>,
+    ui: &mut Ui,
+) -> Option<Vec<ItemDrop>> {
+    ui.horizontal(|ui| {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.label("File");
+            ui.text_edit_singleline(&mut state.file_path);
+            if ui.button("Load").clicked() {
+                match std::fs::read(state.file_path.trim()) {
+                    Ok(contents) => state.load(state.file_path.trim().to_string(), contents),
+                    Err(e) => {
+                        state.error =
+                            Some(format!("Dorothy couldn't open {}: {}", state.file_path, e))
+                    }
+                }
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        if ui.button("Choose File").clicked() {
+            let _ = crate::export::pick_import_file(state.pending.clone(), ".csv,.tsv,.txt");
+        }
+    });
+    if let Some(error) = &state.error {
+        ui.label(error);
+    }
+
+    ui.add_space(5.);
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_label("Preset")
+            .selected_text(&state.mapping.name)
+            .show_ui(ui, |ui| {
+                for preset in presets.iter() {
+                    if ui
+                        .selectable_label(state.mapping.name == preset.name, &preset.name)
+                        .clicked()
+                    {
+                        state.mapping = preset.clone();
+                        state.reread();
+                    }
+                }
+            });
+        ui.text_edit_singleline(&mut state.mapping.name);
+        if ui.button("Save Preset").clicked() && !state.mapping.name.trim().is_empty() {
+            presets.retain(|x| x.name != state.mapping.name);
+            presets.push(state.mapping.clone());
+        }
+        if ui.button("Delete Preset").clicked() {
+            presets.retain(|x| x.name != state.mapping.name);
+        }
+    });
+
+    if state.csv.records.is_empty() {
+        return None;
+    }
+
+    ui.add_space(5.);
+    let mut reread = false;
+    ui.horizontal(|ui| {
+        ui.label("Separator");
+        for (delimiter, label) in [(',', "Comma"), (';', "Semicolon"), ('\t', "Tab")] {
+            reread |= ui
+                .radio_value(&mut state.mapping.delimiter, delimiter, label)
+                .changed();
+        }
+    });
+    reread |= ui
+        .checkbox(&mut state.mapping.has_headers, "First row is a header")
+        .changed();
+
+    ui.heading("Columns");
+    let headers = state.csv.headers(state.mapping.has_headers);
+    egui::Grid::new("import_columns_grid").show(ui, |ui| {
+        for (column, header) in headers.iter().enumerate() {
+            ui.label(header);
+            let field = &mut state.mapping.columns[column];
+            egui::ComboBox::from_id_source(("import_column", column))
+                .selected_text(field.to_string())
+                .show_ui(ui, |ui| {
+                    for option in ImportField::ALL {
+                        reread |= ui
+                            .selectable_value(field, option, option.to_string())
+                            .changed();
+                    }
+                });
+            ui.end_row();
+        }
+    });
+    if reread {
+        state.reread();
+    }
+
+    if state.mapping.column_for(ImportField::Date).is_some() {
+        ui.horizontal(|ui| {
+            ui.label("Date format");
+            ui.add(
+                egui::TextEdit::singleline(&mut state.mapping.date_format)
+                    .hint_text("%Y-%m-%d %H:%M:%S"),
+            );
+        });
+    }
+    if state.mapping.column_for(ImportField::Raid).is_none() {
+        egui::ComboBox::from_label("Raid for every row")
+            .selected_text(state.mapping.default_raid.to_string())
+            .show_ui(ui, |ui| {
+                for raid in Raid::ALL {
+                    ui.selectable_value(&mut state.mapping.default_raid, raid, raid.to_string());
+                }
+            });
+    }
+    if state.mapping.column_for(ImportField::Chest).is_none() {
+        egui::ComboBox::from_label("Chest for every row")
+            .selected_text(state.mapping.default_chest.to_string())
+            .show_ui(ui, |ui| {
+                for chest in ChestType::ALL {
+                    ui.selectable_value(&mut state.mapping.default_chest, chest, chest.to_string());
+                }
+            });
+    }
+
+    if state.mapping.column_for(ImportField::Raid).is_some() {
+        ui.collapsing("Raid values", |ui| {
+            place_value_mappings(
+                "import_raid_values",
+                &mut state.mapping.raid_values,
+                &Raid::ALL,
+                ui,
+            );
+        });
+    }
+    if state.mapping.column_for(ImportField::Item).is_some() {
+        ui.collapsing("Item values", |ui| {
+            place_value_mappings(
+                "import_item_values",
+                &mut state.mapping.item_values,
+                &Item::ALL,
+                ui,
+            );
+        });
+    }
+    if state.mapping.column_for(ImportField::Chest).is_some() {
+        ui.collapsing("Chest values", |ui| {
+            place_value_mappings(
+                "import_chest_values",
+                &mut state.mapping.chest_values,
+                &ChestType::ALL,
+                ui,
+            );
+        });
+    }
+
+    let preview = state.preview();
+    ui.heading("Preview");
+    egui::Grid::new("import_preview_grid")
+        .striped(true)
+        .show(ui, |ui| {
+            for drop in preview.drops.iter().take(10) {
+                ui.label(&drop.date_obtained);
+                ui.label(drop.raid.to_string());
+                ui.label(drop.item.to_string());
+                ui.label(drop.chest.to_string());
+                ui.end_row();
+            }
+        });
+    ui.label(format!(
+        "{} drops will be imported, {} rows skipped.",
+        preview.drops.len(),
+        preview.skipped.len()
+    ));
+    if !preview.skipped.is_empty() {
+        ui.collapsing("Skipped rows", |ui| {
+            for reason in preview.skipped.iter().take(50) {
+                ui.label(reason);
+            }
+        });
+    }
+
+    if ui.button("Import").clicked() && !preview.drops.is_empty() {
+        return Some(preview.drops.clone());
+    }
+    None
+}
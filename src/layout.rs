@@ -0,0 +1,448 @@
+use crate::textures::item_texture;
+use crate::{
+    hotkeys, place_image_button_combo, AppSettings, ChestType, DorothyConfig, Item, PBHLHonors,
+    Raid, UiTab,
+};
+use eframe::egui::{self, Color32, CursorIcon, Sense, Stroke, Ui};
+use serde::{Deserialize, Serialize};
+
+pub const MAX_COLUMNS: usize = 8;
+
+/// Tabs with item buttons.
+pub const LAYOUT_TABS: [UiTab; 6] = [
+    UiTab::Akasha,
+    UiTab::PBHL,
+    UiTab::GOHL,
+    UiTab::Hosts,
+    UiTab::SixDragons,
+    UiTab::EternitySand,
+];
+
+/// An item button on a tab.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct LayoutButton {
+    pub item: Item,
+    /// `None` logs drops for the raid picked under Current Raid.
+    pub raid: Option<Raid>,
+    pub chest: ChestType,
+    pub visible: bool,
+}
+
+impl LayoutButton {
+    fn new(item: Item, raid: Option<Raid>, chest: ChestType) -> Self {
+        Self {
+            item,
+            raid,
+            chest,
+            visible: true,
+        }
+    }
+
+    fn blue_chest(item: Item, raid: Option<Raid>) -> Self {
+        let chest = match item {
+            Item::NoDrop => ChestType::None,
+            _ => ChestType::Blue,
+        };
+        Self::new(item, raid, chest)
+    }
+
+    pub fn description(&self) -> String {
+        let raid = match self.raid {
+            Some(raid) => raid.to_string(),
+            None => "the Current Raid".to_string(),
+        };
+        match self.chest {
+            ChestType::Host | ChestType::Flip => {
+                format!("{} from {} in a {}", self.item, raid, self.chest)
+            }
+            _ => format!("{} from {}", self.item, raid),
+        }
+    }
+}
+
+/// Order, rows and visibility of a tab's item buttons.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct TabLayout {
+    pub tab: UiTab,
+    /// Buttons per row past the ones in `rows`.
+    pub columns: usize,
+    /// Buttons in each of the first rows, hidden ones included, for tabs whose rows aren't all
+    /// as long.
+    #[serde(default)]
+    pub rows: Vec<usize>,
+    pub buttons: Vec<LayoutButton>,
+}
+
+impl TabLayout {
+    /// The layout Dorothy comes with, or `None` for tabs without item buttons.
+    pub fn default_for(tab: &UiTab) -> Option<Self> {
+        let blue_chest = |raid: Raid, items: &[Item]| -> Vec<LayoutButton> {
+            items
+                .iter()
+                .map(|item| LayoutButton::blue_chest(*item, Some(raid)))
+                .collect()
+        };
+        let (rows, buttons) = match tab {
+            UiTab::Akasha => (
+                vec![4, 3, 3, 3],
+                blue_chest(
+                    Raid::Akasha,
+                    &[
+                        Item::NoDrop,
+                        Item::HollowKey,
+                        Item::SilverCentrum,
+                        Item::GoldBrick,
+                        Item::CoronationRing,
+                        Item::ChampionMerit,
+                        Item::WeaponPlusMark1,
+                        Item::LineageRing,
+                        Item::SupremeMerit,
+                        Item::WeaponPlusMark2,
+                        Item::IntricacyRing,
+                        Item::LegendaryMerit,
+                        Item::WeaponPlusMark3,
+                    ],
+                ),
+            ),
+            UiTab::PBHL => (
+                vec![3, 2],
+                blue_chest(
+                    Raid::PBHL,
+                    &[
+                        Item::NoDrop,
+                        Item::GoldBrick,
+                        Item::CoronationRing,
+                        Item::LineageRing,
+                        Item::IntricacyRing,
+                    ],
+                ),
+            ),
+            UiTab::GOHL => (
+                vec![4, 2, 2, 2],
+                blue_chest(
+                    Raid::GOHL,
+                    &[
+                        Item::NoDrop,
+                        Item::VerdantAzurite,
+                        Item::SilverCentrum,
+                        Item::GoldBrick,
+                        Item::CoronationRing,
+                        Item::ChampionMerit,
+                        Item::LineageRing,
+                        Item::SupremeMerit,
+                        Item::IntricacyRing,
+                        Item::LegendaryMerit,
+                    ],
+                ),
+            ),
+            UiTab::Hosts => (
+                vec![4, 3],
+                [
+                    (Raid::UBHL, ChestType::Host),
+                    (Raid::UBHL, ChestType::Flip),
+                    (Raid::PBHL, ChestType::Host),
+                    (Raid::Xeno, ChestType::Flip),
+                    (Raid::Huanglong, ChestType::Host),
+                    (Raid::Qilin, ChestType::Host),
+                    (Raid::HLQL, ChestType::Host),
+                ]
+                .iter()
+                .map(|(raid, chest)| LayoutButton::new(Item::GoldBrick, Some(*raid), *chest))
+                .collect(),
+            ),
+            UiTab::SixDragons => (
+                vec![1, 3, 3, 1],
+                vec![
+                    LayoutButton::blue_chest(Item::NoDrop, None),
+                    LayoutButton::blue_chest(Item::BrimstoneEarrings, Some(Raid::Wilnas)),
+                    LayoutButton::blue_chest(Item::PermafrostEarrings, Some(Raid::Wamdus)),
+                    LayoutButton::blue_chest(Item::BrickearthEarrings, Some(Raid::Galleon)),
+                    LayoutButton::blue_chest(Item::JetstreamEarrings, Some(Raid::Ewiyar)),
+                    LayoutButton::blue_chest(Item::SunbeamEarrings, Some(Raid::LuWoh)),
+                    LayoutButton::blue_chest(Item::NightshadeEarrings, Some(Raid::Fediel)),
+                    LayoutButton::blue_chest(Item::EternitySand, None),
+                ],
+            ),
+            UiTab::EternitySand => (
+                vec![1],
+                vec![LayoutButton::new(Item::EternitySand, None, ChestType::Gold)],
+            ),
+            _ => return None,
+        };
+        Some(Self {
+            tab: tab.clone(),
+            columns: rows.iter().copied().max().unwrap_or(1),
+            rows,
+            buttons,
+        })
+    }
+
+    /// The default layout with the items hidden before layouts could be edited left out.
+    fn from_active_items(tab: &UiTab, settings: &DorothyConfig) -> Option<Self> {
+        let mut layout = Self::default_for(tab)?;
+        let visible: Vec<bool> = match tab {
+            UiTab::Akasha => settings.active_items[..13].to_vec(),
+            UiTab::PBHL => settings.active_items[13..18].to_vec(),
+            UiTab::GOHL => settings.active_items[18..28].to_vec(),
+            UiTab::Hosts => [&settings.active_items[28..], &settings.active_items_2[..3]].concat(),
+            _ => vec![],
+        };
+        for (button, visible) in layout.buttons.iter_mut().zip(visible) {
+            button.visible = visible;
+        }
+        Some(layout)
+    }
+
+    /// Whether the button at `index` goes at the start of a new row.
+    pub fn starts_row(&self, index: usize) -> bool {
+        let mut row_start = 0;
+        for &row in self.rows.iter().filter(|&&row| row > 0) {
+            if index < row_start + row {
+                return index > 0 && index == row_start;
+            }
+            row_start += row;
+        }
+        index > 0 && (index - row_start) % self.columns.max(1) == 0
+    }
+
+    /// Visible buttons in order. Buttons without a raid log drops for `selected_raid`.
+    pub fn visible_buttons(&self, selected_raid: Raid) -> Vec<(Item, Raid, ChestType)> {
+        self.buttons
+            .iter()
+            .filter(|x| x.visible)
+            .map(|x| (x.item, x.raid.unwrap_or(selected_raid), x.chest))
+            .collect()
+    }
+}
+
+impl DorothyConfig {
+    /// Saves a layout for every tab that doesn't have one yet, made from the items hidden before
+    /// layouts could be edited. Called once settings are loaded so `tab_layout` can borrow them.
+    pub fn migrate_item_layouts(&mut self) {
+        for tab in LAYOUT_TABS {
+            if !self.item_layouts.iter().any(|x| x.tab == tab) {
+                if let Some(layout) = TabLayout::from_active_items(&tab, self) {
+                    self.item_layouts.push(layout);
+                }
+            }
+        }
+    }
+
+    /// Layout of `tab`, or `None` for tabs without item buttons.
+    pub fn tab_layout(&self, tab: &UiTab) -> Option<&TabLayout> {
+        self.item_layouts.iter().find(|x| x.tab == *tab)
+    }
+
+    /// Layout of `tab` to edit, saved from now on if it wasn't yet.
+    pub fn tab_layout_mut(&mut self, tab: &UiTab) -> Option<&mut TabLayout> {
+        if !self.item_layouts.iter().any(|x| x.tab == *tab) {
+            let layout = TabLayout::from_active_items(tab, self)?;
+            self.item_layouts.push(layout);
+        }
+        self.item_layouts.iter_mut().find(|x| x.tab == *tab)
+    }
+}
+
+/// The current tab's item buttons in its layout's columns.
+pub fn place_item_grid(
+    settings: &mut AppSettings,
+    honors: &PBHLHonors,
+    selected_raid: Raid,
+    ui: &mut Ui,
+) {
+    let tab = settings.app_settings.current_ui_tab.clone();
+    let layout = match settings.app_settings.tab_layout(&tab) {
+        Some(layout) => layout,
+        None => return,
+    };
+    // Placing buttons needs the settings, so the layout can't stay borrowed from them. Rows are
+    // counted with hidden buttons, so a row can start at a hidden one.
+    let mut buttons = vec![];
+    let mut starts_row = false;
+    for (index, button) in layout.buttons.iter().enumerate() {
+        starts_row |= layout.starts_row(index);
+        if button.visible {
+            let raid = button.raid.unwrap_or(selected_raid);
+            buttons.push(((button.item, raid, button.chest), starts_row));
+            starts_row = false;
+        }
+    }
+    let keys = match settings.app_settings.quick_entry {
+        true => hotkeys::quick_entry_keys(&settings.app_settings.hotkeys, buttons.len()),
+        false => vec![],
+    };
+    egui::Grid::new(format!("{:?}_item_grid", tab))
+        .spacing((
+            settings.app_settings.grid_spacing_x,
+            settings.app_settings.grid_spacing_y,
+        ))
+        .show(ui, |ui| {
+            ui.style_mut().wrap = Some(false);
+            let vertical = settings.app_settings.vertical_grid;
+            for (pos, ((item, raid, chest), starts_row)) in buttons.into_iter().enumerate() {
+                if pos > 0 && starts_row {
+                    ui.end_row();
+                }
+                let key = keys.get(pos).copied();
+                let place_button = |ui: &mut Ui| {
+                    place_image_button_combo(item, raid, chest, key, honors, settings, ui)
+                };
+                if vertical {
+                    ui.vertical(place_button);
+                } else {
+                    ui.horizontal(place_button);
+                }
+            }
+        });
+}
+
+/// State of the Item Layout window.
+#[derive(Default)]
+pub struct LayoutEditor {
+    pub open: bool,
+    pub tab: UiTab,
+    /// Button being dragged to a new place.
+    dragging: Option<usize>,
+}
+
+/// Picking a tab's columns and which of its items are shown, and dragging its items into a new
+/// order.
+pub fn place_layout_editor(editor: &mut LayoutEditor, settings: &mut DorothyConfig, ui: &mut Ui) {
+    if !LAYOUT_TABS.contains(&editor.tab) {
+        editor.tab = match LAYOUT_TABS.contains(&settings.current_ui_tab) {
+            true => settings.current_ui_tab.clone(),
+            false => UiTab::Akasha,
+        };
+    }
+    egui::ComboBox::from_label("Tab")
+        .selected_text(format!("{:?}", editor.tab))
+        .show_ui(ui, |ui| {
+            for tab in LAYOUT_TABS {
+                let name = format!("{:?}", tab);
+                if ui.selectable_value(&mut editor.tab, tab, name).clicked() {
+                    editor.dragging = None;
+                }
+            }
+        });
+    let layout = match settings.tab_layout_mut(&editor.tab) {
+        Some(layout) => layout,
+        None => return,
+    };
+    let columns = ui.add(egui::Slider::new(&mut layout.columns, 1..=MAX_COLUMNS).text("Columns"));
+    if columns.changed() {
+        // Picking columns lays every row out the same
+        layout.rows.clear();
+    }
+    ui.label("Drag an item onto another to move it there. Unticked items are hidden.");
+    ui.add_space(5.);
+
+    let mut target = None;
+    let row_starts: Vec<bool> = (0..layout.buttons.len())
+        .map(|index| layout.starts_row(index))
+        .collect();
+    egui::Grid::new("layout_editor_grid")
+        .spacing((10., 10.))
+        .show(ui, |ui| {
+            for (index, button) in layout.buttons.iter_mut().enumerate() {
+                if row_starts[index] {
+                    ui.end_row();
+                }
+                ui.vertical(|ui| {
+                    let tint = match button.visible {
+                        true => Color32::WHITE,
+                        false => Color32::from_white_alpha(60),
+                    };
+                    let texture = item_texture(
+                        ui.ctx(),
+                        button.item,
+                        button.raid.unwrap_or(Raid::None),
+                        button.chest,
+                    );
+                    let response = ui.add(
+                        egui::ImageButton::new(texture, (32., 32.))
+                            .tint(tint)
+                            .selected(editor.dragging == Some(index))
+                            .sense(Sense::click_and_drag()),
+                    );
+                    if response.drag_started() {
+                        editor.dragging = Some(index);
+                    }
+                    if editor.dragging.is_some() && ui.rect_contains_pointer(response.rect) {
+                        target = Some(index);
+                        ui.painter().rect_stroke(
+                            response.rect.expand(2.),
+                            3.,
+                            Stroke::new(2., ui.visuals().selection.stroke.color),
+                        );
+                    }
+                    response
+                        .on_hover_cursor(CursorIcon::Grab)
+                        .on_hover_text(button.description());
+                    ui.checkbox(&mut button.visible, "");
+                });
+            }
+        });
+
+    if let Some(from) = editor.dragging {
+        ui.output().cursor_icon = CursorIcon::Grabbing;
+        if ui.input().pointer.any_released() {
+            if let Some(to) = target {
+                let button = layout.buttons.remove(from);
+                layout.buttons.insert(to, button);
+            }
+            editor.dragging = None;
+        }
+    }
+
+    ui.add_space(5.);
+    if ui.button("Reset Tab").clicked() {
+        if let Some(default_layout) = TabLayout::default_for(&editor.tab) {
+            *layout = default_layout;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_starts(layout: &TabLayout) -> Vec<usize> {
+        (0..layout.buttons.len())
+            .filter(|&index| layout.starts_row(index))
+            .collect()
+    }
+
+    #[test]
+    fn default_layouts_keep_their_rows() {
+        let default_rows = |tab| row_starts(&TabLayout::default_for(&tab).unwrap());
+        assert_eq!(default_rows(UiTab::Akasha), vec![4, 7, 10]);
+        assert_eq!(default_rows(UiTab::PBHL), vec![3]);
+        assert_eq!(default_rows(UiTab::GOHL), vec![4, 6, 8]);
+        assert_eq!(default_rows(UiTab::Hosts), vec![4]);
+        assert_eq!(default_rows(UiTab::SixDragons), vec![1, 4, 7]);
+        assert_eq!(default_rows(UiTab::EternitySand), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn buttons_past_the_rows_go_in_columns() {
+        let mut layout = TabLayout::default_for(&UiTab::Akasha).unwrap();
+        layout.rows = vec![2];
+        layout.columns = 5;
+        assert_eq!(row_starts(&layout), vec![2, 7, 12]);
+        layout.rows.clear();
+        assert_eq!(row_starts(&layout), vec![5, 10]);
+    }
+
+    #[test]
+    fn migrated_layouts_keep_hidden_items_hidden() {
+        let mut settings = DorothyConfig::default();
+        settings.active_items[1] = false;
+        settings.migrate_item_layouts();
+        let akasha = settings.tab_layout(&UiTab::Akasha).unwrap();
+        assert!(!akasha.buttons[1].visible);
+        assert_eq!(akasha.rows, vec![4, 3, 3, 3]);
+        for tab in LAYOUT_TABS {
+            assert!(settings.tab_layout(&tab).is_some());
+        }
+    }
+}
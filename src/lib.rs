@@ -3,22 +3,39 @@
 #![feature(derive_default_enum)]
 #![feature(drain_filter)]
 
-use eframe::egui::{widgets, Response, Sense, Ui, Widget, WidgetInfo, WidgetType};
+use eframe::egui::{
+    widgets, DragValue, Key, Response, RichText, Sense, Ui, Widget, WidgetInfo, WidgetType,
+};
 use eframe::epaint::{ColorImage, Rounding, TextureId, Vec2};
 use serde::{Deserialize, Serialize};
 
 #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
 use chrono::{DateTime, Local};
-#[cfg(target_arch = "wasm32")]
-use chrono::{DateTime, NaiveDateTime, Utc};
-#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
-use self_update::cargo_crate_version;
-use std::error::Error;
-use std::fs::{self, OpenOptions};
-use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
 use std::{f32::INFINITY, fmt};
 
 pub mod app;
+pub mod export;
+pub mod gacha;
+pub mod highlight;
+pub mod hotkeys;
+pub mod import;
+pub mod layout;
+pub mod overlay;
+pub mod pulls;
+pub mod recent;
+pub mod share;
+pub mod stats;
+pub mod theme;
+pub mod textures;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod updater;
+
+pub use export::{export, ExportFilter, ExportFormat};
+pub use stats::{
+    drop_count, drop_rate, dry_streak, item_count, kill_count, no_drop_count, total_kills,
+};
 
 pub static BLUE_CHEST: &[u8] = include_bytes!("./images/blue_chest.png");
 pub static NO_BLUE_CHEST: &[u8] = include_bytes!("./images/no_blue_chest.png");
@@ -56,80 +73,48 @@ pub fn get_time() -> String {
     let logged_time: DateTime<Local> = Local::now();
     logged_time.format("%Y-%m-%d %H:%M:%S").to_string()
 }
-// chrono crate doesn't support wasm32 arch yet, workaround
+// chrono crate doesn't support wasm32 arch yet, workaround. Written in local time like on
+// desktop so drops logged in either can be compared.
 #[cfg(target_arch = "wasm32")]
 pub fn get_time() -> String {
-    let now = js_sys::Date::now().to_string();
-    let now_as_epoch = now.parse::<i64>().unwrap();
-    let datetime = DateTime::<Utc>::from_utc(
-        NaiveDateTime::from_timestamp_opt(
-            now_as_epoch / 1000,
-            (now_as_epoch % 1000) as u32 * 1_000_000,
-        )
-        .unwrap(),
-        Utc,
-    );
-    datetime.to_string()
+    let now = js_sys::Date::new_0();
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}:{:02}",
+        now.get_full_year(),
+        now.get_month() + 1,
+        now.get_date(),
+        now.get_hours(),
+        now.get_minutes(),
+        now.get_seconds()
+    )
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub enum ReleaseStatus {
-    NewVersion,
-    UpToDate,
+/// Where the pull calculator's total comes from.
+#[derive(PartialEq, Copy, Clone, Default, Debug)]
+pub struct PullBreakdown {
+    pub crystal_pulls: u32,
+    pub ticket_pulls: u32,
+    pub shrimp_pulls: u32,
+    /// Crystals left over that aren't enough for another pull.
+    pub leftover_crystals: u32,
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn check_for_update() -> Result<ReleaseStatus, Box<dyn (::std::error::Error)>> {
-    let releases = self_update::backends::github::ReleaseList::configure()
-        .repo_owner("NadyaNayme")
-        .repo_name("Dorothy-egui")
-        .build()?
-        .fetch()?;
-
-    if releases.is_empty() {
-        return Ok(ReleaseStatus::UpToDate);
-    }
-
-    let latest_release = &releases[0];
-    let is_new =
-        self_update::version::bump_is_greater(cargo_crate_version!(), &latest_release.version)?;
-
-    if is_new {
-        Ok(ReleaseStatus::NewVersion)
-    } else {
-        Ok(ReleaseStatus::UpToDate)
+impl PullBreakdown {
+    pub fn total(&self) -> u32 {
+        self.crystal_pulls
+            .saturating_add(self.ticket_pulls)
+            .saturating_add(self.shrimp_pulls)
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn self_update() -> Result<(), Box<dyn ::std::error::Error>> {
-    let _releases = self_update::backends::github::ReleaseList::configure()
-        .repo_owner("NadyaNayme")
-        .repo_name("Dorothy-egui")
-        .build()?
-        .fetch()?;
-
-    let _status = self_update::backends::github::Update::configure()
-        .repo_owner("NadyaNayme")
-        .repo_name("Dorothy-egui")
-        .bin_name("dorothy-egui_bin")
-        .show_download_progress(true)
-        .current_version(cargo_crate_version!())
-        .no_confirm(true)
-        .build()?
-        .update()?;
-    Ok(())
-}
-
-pub fn calculate_pulls(crystals: f32, tenners: f32, singles: f32, shrimps: f32) -> String {
-    let crystal_ten_pulls = ((crystals / 3000.0).floor()) * 10.0;
-    let remaining_crystals_for_single_pulls = crystals % 3000.0;
-    let crystal_single_pulls = (remaining_crystals_for_single_pulls / 300.0).floor();
-    let total_pulls =
-        (crystal_ten_pulls + crystal_single_pulls + (tenners * 10.0) + singles + shrimps).to_string();
-    let total = total_pulls;
-    let spark_percentage = get_percentage(total.parse::<f32>().unwrap(), 300.0);
-    "Total: ".to_owned() + &total + " pulls (" + &spark_percentage + ")"
+/// Crystals buy 10-pulls at 3000 and singles at 300, so every 300 crystals are a pull.
+pub fn calculate_pulls(crystals: u32, tenners: u32, singles: u32, shrimps: u32) -> PullBreakdown {
+    PullBreakdown {
+        crystal_pulls: crystals / pulls::CRYSTALS_PER_PULL,
+        ticket_pulls: tenners.saturating_mul(10).saturating_add(singles),
+        shrimp_pulls: shrimps,
+        leftover_crystals: crystals % pulls::CRYSTALS_PER_PULL,
+    }
 }
 
 pub fn get_percentage(x: f32, y: f32) -> String {
@@ -173,6 +158,11 @@ pub fn load_icon(icon_bytes: &[u8]) -> Option<epi::IconData> {
     }
 }
 
+pub fn format_rate(rate: f32) -> String {
+    use format_num::NumberFormat;
+    NumberFormat::new().format(".2%", rate)
+}
+
 //noinspection ALL
 pub fn place_total_header(
     raid: Raid,
@@ -181,27 +171,7 @@ pub fn place_total_header(
     settings: &AppSettings,
     ui: &mut Ui,
 ) {
-    let mut total_drops_of_item = settings
-        .droplog
-        .drop
-        .iter()
-        .filter(|x| x.raid == raid && x.chest != ChestType::Host && x.chest != ChestType::Flip)
-        .count();
-    if raid == Raid::UBHL || raid == Raid::Xeno {
-        total_drops_of_item = settings
-            .droplog
-            .drop
-            .iter()
-            .filter(|x| {
-                x.raid == Raid::UBHL
-                    || x.raid == Raid::Xeno
-                    || (x.raid == Raid::PBHL && x.chest == ChestType::Host)
-                    || x.raid == Raid::HLQL
-                    || x.raid == Raid::Huanglong
-                    || x.raid == Raid::Qilin
-            })
-            .count();
-    }
+    let total_drops_of_item = settings.stats.total_kills(raid);
     let raid_heading: String = match raid {
         Raid::Akasha => "Akasha - ".to_string(),
         Raid::PBHL => "PBHL - ".to_string(),
@@ -235,7 +205,14 @@ pub fn place_total_header(
         Raid::None => "".to_string(),
     };
     ui.add_space(20.);
-    ui.heading(raid_heading + &total_drops_of_item.to_string());
+    let heading = ui.heading(raid_heading + &total_drops_of_item.to_string());
+    // The Hosts total is made of several raids so there's no single raid to break down
+    if raid != Raid::UBHL && raid != Raid::Xeno {
+        let raid_kills = settings.stats.kills(raid);
+        if raid_kills.kills > 0 {
+            heading.on_hover_text(raid_kills.breakdown());
+        }
+    }
     ui.add_space(5.);
 }
 
@@ -246,31 +223,6 @@ pub fn place_percentage_label(
     settings: &AppSettings,
     ui: &mut Ui,
 ) {
-    let mut total_raid_drops = settings
-        .droplog
-        .drop
-        .iter()
-        .filter(|x| x.raid == raid && x.chest != ChestType::Host && x.chest != ChestType::Flip)
-        .count();
-    if chest == ChestType::Host {
-        total_raid_drops = settings
-            .droplog
-            .drop
-            .iter()
-            .filter(|x| {
-                x.raid == raid
-                    || (x.raid == Raid::PBHL
-                        && x.chest != ChestType::Blue
-                        && x.chest != ChestType::None)
-            })
-            .count();
-    }
-    let no_drop_count = settings
-        .droplog
-        .drop
-        .iter()
-        .filter(|x| x.item == Item::NoDrop && x.raid == raid)
-        .count();
     let mut label_text: String = match item {
         Item::NoDrop => "No Drop: ".to_string(),
         Item::HollowKey => "Hollow Key: ".to_string(),
@@ -304,50 +256,601 @@ pub fn place_percentage_label(
     {
         label_text = format!("{} {} {}", raid, chest, ": ");
     }
-    let items_dropped = settings
-        .droplog
-        .drop
-        .iter()
-        .filter(|x| x.item == item && x.raid == raid && x.chest == chest)
-        .count();
-
-    if settings.app_settings.droprate_by_kills {
-        use format_num::NumberFormat;
-        let mut drop_percent_rate = format!(
-            " ({})",
-            NumberFormat::new().format(".2%", items_dropped as f32 / total_raid_drops as f32)
-        );
-        if items_dropped == 0 {
-            drop_percent_rate = "".to_string();
-        }
-        ui.label(label_text + &items_dropped.to_string() + &drop_percent_rate);
-    } else if !settings.app_settings.droprate_by_kills && chest != ChestType::None {
-        use format_num::NumberFormat;
-        let mut drop_percent_rate = format!(
-            " ({})",
-            NumberFormat::new().format(
-                ".2%",
-                items_dropped as f32 / (total_raid_drops as f32 - no_drop_count as f32)
-            )
-        );
-        if items_dropped == 0 {
-            drop_percent_rate = "".to_string();
-        }
+    let items_dropped = settings.stats.item_count(raid, item, chest);
+
+    if settings.app_settings.droprate_by_kills || chest != ChestType::None {
+        let drop_percent_rate = match settings.stats.drop_rate(
+            raid,
+            item,
+            chest,
+            settings.app_settings.droprate_by_kills,
+        ) {
+            Some(rate) => format!(" ({})", format_rate(rate)),
+            None => "".to_string(),
+        };
         ui.label(label_text + &items_dropped.to_string() + &drop_percent_rate);
     } else {
-        ui.label("No Drop: ".to_string() + &no_drop_count.to_string());
+        ui.label("No Drop: ".to_string() + &settings.stats.no_drop_count(raid).to_string());
     }
 }
 
-pub fn place_image_button_combo(
-    item: Item,
-    raid: Raid,
-    chest: ChestType,
-    honors: &PBHLHonors,
-    settings: &mut AppSettings,
+/// Every raid's Drop Totals for the current tab, or for every tab with "Show All Drop Totals".
+pub fn place_drop_totals(settings: &AppSettings, ui: &mut Ui) {
+    if settings.app_settings.current_ui_tab == UiTab::Akasha
+        || settings.app_settings.show_all_drops
+    {
+        place_total_header(
+            Raid::Akasha,
+            Item::NoDrop,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::NoDrop,
+            ChestType::None,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::HollowKey,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::SilverCentrum,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::GoldBrick,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::CoronationRing,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::LineageRing,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::IntricacyRing,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::ChampionMerit,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::SupremeMerit,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::LegendaryMerit,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::WeaponPlusMark1,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::WeaponPlusMark2,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Akasha,
+            Item::WeaponPlusMark3,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+    }
+    if settings.app_settings.current_ui_tab == UiTab::PBHL
+        || settings.app_settings.show_all_drops
+    {
+        place_total_header(
+            Raid::PBHL,
+            Item::NoDrop,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::PBHL,
+            Item::NoDrop,
+            ChestType::None,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::PBHL,
+            Item::GoldBrick,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::PBHL,
+            Item::CoronationRing,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::PBHL,
+            Item::LineageRing,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::PBHL,
+            Item::IntricacyRing,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+    }
+    if settings.app_settings.current_ui_tab == UiTab::GOHL
+        || settings.app_settings.show_all_drops
+    {
+        place_total_header(
+            Raid::GOHL,
+            Item::NoDrop,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::GOHL,
+            Item::NoDrop,
+            ChestType::None,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::GOHL,
+            Item::VerdantAzurite,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::GOHL,
+            Item::SilverCentrum,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::GOHL,
+            Item::GoldBrick,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::GOHL,
+            Item::CoronationRing,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::GOHL,
+            Item::LineageRing,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::GOHL,
+            Item::IntricacyRing,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::GOHL,
+            Item::ChampionMerit,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::GOHL,
+            Item::SupremeMerit,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::GOHL,
+            Item::LegendaryMerit,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+    }
+
+    if settings.app_settings.current_ui_tab == UiTab::Hosts
+        || settings.app_settings.show_all_drops
+    {
+        place_total_header(
+            Raid::UBHL,
+            Item::NoDrop,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::UBHL,
+            Item::GoldBrick,
+            ChestType::Host,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::UBHL,
+            Item::GoldBrick,
+            ChestType::Flip,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::PBHL,
+            Item::GoldBrick,
+            ChestType::Host,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Xeno,
+            Item::GoldBrick,
+            ChestType::Flip,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Huanglong,
+            Item::GoldBrick,
+            ChestType::Host,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Qilin,
+            Item::GoldBrick,
+            ChestType::Host,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::HLQL,
+            Item::GoldBrick,
+            ChestType::Host,
+            settings,
+            ui,
+        );
+    }
+
+    if settings.app_settings.current_ui_tab == UiTab::SixDragons
+        || settings.app_settings.show_all_drops
+    {
+        place_total_header(
+            Raid::Wilnas,
+            Item::NoDrop,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Wilnas,
+            Item::NoDrop,
+            ChestType::None,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Wilnas,
+            Item::BrimstoneEarrings,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Wilnas,
+            Item::EternitySand,
+            ChestType::Gold,
+            settings,
+            ui,
+        );
+        place_total_header(
+            Raid::Wamdus,
+            Item::NoDrop,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Wamdus,
+            Item::NoDrop,
+            ChestType::None,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Wamdus,
+            Item::PermafrostEarrings,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Wamdus,
+            Item::EternitySand,
+            ChestType::Gold,
+            settings,
+            ui,
+        );
+        place_total_header(
+            Raid::Galleon,
+            Item::NoDrop,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Galleon,
+            Item::NoDrop,
+            ChestType::None,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Galleon,
+            Item::BrickearthEarrings,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Galleon,
+            Item::EternitySand,
+            ChestType::Gold,
+            settings,
+            ui,
+        );
+        place_total_header(
+            Raid::Ewiyar,
+            Item::NoDrop,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Ewiyar,
+            Item::NoDrop,
+            ChestType::None,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Ewiyar,
+            Item::JetstreamEarrings,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Ewiyar,
+            Item::EternitySand,
+            ChestType::Gold,
+            settings,
+            ui,
+        );
+        place_total_header(
+            Raid::LuWoh,
+            Item::NoDrop,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::LuWoh,
+            Item::NoDrop,
+            ChestType::None,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::LuWoh,
+            Item::SunbeamEarrings,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::LuWoh,
+            Item::EternitySand,
+            ChestType::Gold,
+            settings,
+            ui,
+        );
+        place_total_header(
+            Raid::Fediel,
+            Item::NoDrop,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Fediel,
+            Item::NoDrop,
+            ChestType::None,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Fediel,
+            Item::NightshadeEarrings,
+            ChestType::Blue,
+            settings,
+            ui,
+        );
+        place_percentage_label(
+            Raid::Fediel,
+            Item::EternitySand,
+            ChestType::Gold,
+            settings,
+            ui,
+        );
+
+    }
+    if settings.app_settings.current_ui_tab == UiTab::EternitySand
+        || settings.app_settings.show_all_drops
+    {
+
+        let total_drops_of_item: u32 = settings
+            .droplog
+            .drop
+            .iter()
+            .filter(|x| x.item == Item::EternitySand)
+            .map(|x| x.quantity)
+            .sum();
+
+        ui.add_space(20.);
+        ui.heading("Eternity Sands (All Raids) - ".to_owned() + &total_drops_of_item.to_string());
+        ui.add_space(5.);
+    }
+}
+
+/// Checkbox that adds or removes `value` from a list of selected values.
+pub fn place_filter_checkbox<T: PartialEq + Copy>(
+    value: T,
+    label: String,
+    selected: &mut Vec<T>,
     ui: &mut Ui,
 ) {
-    let image_item_name = match item {
+    let mut is_selected = selected.contains(&value);
+    if ui.checkbox(&mut is_selected, label).changed() {
+        if is_selected {
+            selected.push(value);
+        } else {
+            selected.retain(|x| *x != value);
+        }
+    }
+}
+
+/// Embedded icon for an item. Host and flip gold bars get the icon of the raid they came from.
+pub fn item_image(item: Item, raid: Raid, chest: ChestType) -> &'static [u8] {
+    let mut item_image = match item {
+        Item::NoDrop => NO_BLUE_CHEST,
+        Item::HollowKey => HOLLOW_KEY,
+        Item::VerdantAzurite => VERDANT_AZURITE,
+        Item::SilverCentrum => SILVER_CENTRUM,
+        Item::GoldBrick => GOLD_BAR,
+        Item::ChampionMerit => C_MERIT,
+        Item::SupremeMerit => S_MERIT,
+        Item::LegendaryMerit => L_MERIT,
+        Item::CoronationRing => C_RING,
+        Item::LineageRing => L_RING,
+        Item::IntricacyRing => I_RING,
+        Item::WeaponPlusMark1 => P_MARK_1,
+        Item::WeaponPlusMark2 => P_MARK_2,
+        Item::WeaponPlusMark3 => P_MARK_3,
+        Item::BrimstoneEarrings => FIRE_EARRING,
+        Item::PermafrostEarrings => WATER_EARRING,
+        Item::BrickearthEarrings => EARTH_EARRING,
+        Item::JetstreamEarrings => WIND_EARRING,
+        Item::NightshadeEarrings => DARK_EARRING,
+        Item::SunbeamEarrings => LIGHT_EARRING,
+        Item::EternitySand => ETERNITY_SAND,
+    };
+    if chest == ChestType::Host {
+        if raid == Raid::Huanglong {
+            item_image = HL_HOST_BAR;
+        }
+        if raid == Raid::Qilin {
+            item_image = QL_HOST_BAR;
+        }
+        if raid == Raid::HLQL {
+            item_image = HLQL_HOST_BAR;
+        }
+        if raid == Raid::UBHL {
+            item_image = UBHL_HOST_BAR;
+        }
+        if raid == Raid::PBHL {
+            item_image = PBHL_HOST_BAR;
+        }
+    }
+    if chest == ChestType::Flip {
+        if raid == Raid::UBHL {
+            item_image = UBHL_FLIP_BAR;
+        }
+        if raid == Raid::Xeno {
+            item_image = XENO_FLIP_BAR;
+        }
+    }
+    item_image
+}
+
+/// Raid a tab's Drop Totals and stats are shown for. Tabs without a fixed raid use `selected_raid`.
+pub fn tab_raid(tab: &UiTab, selected_raid: Raid) -> Raid {
+    match tab {
+        UiTab::Akasha => Raid::Akasha,
+        UiTab::PBHL => Raid::PBHL,
+        UiTab::GOHL => Raid::GOHL,
+        UiTab::Hosts => Raid::UBHL,
+        _ => selected_raid,
+    }
+}
+
+/// Item buttons of the current tab in the order its layout puts them, leaving out hidden ones.
+/// Buttons without a fixed raid log drops for `selected_raid`.
+pub fn tab_buttons(settings: &DorothyConfig, selected_raid: Raid) -> Vec<(Item, Raid, ChestType)> {
+    settings
+        .tab_layout(&settings.current_ui_tab)
+        .map(|layout| layout.visible_buttons(selected_raid))
+        .unwrap_or_default()
+}
+
+/// Name an item's icon is loaded under as a texture.
+pub fn item_texture_name(item: Item) -> &'static str {
+    match item {
         Item::NoDrop => "no_drop.png",
         Item::HollowKey => "hollow_key.png",
         Item::VerdantAzurite => "verdant_azurite.png",
@@ -369,7 +872,18 @@ pub fn place_image_button_combo(
         Item::SunbeamEarrings => "light_earrings.png",
         Item::NightshadeEarrings => "dark_earrings.png",
         Item::EternitySand => "eternity_sand.png",
-    };
+    }
+}
+
+pub fn place_image_button_combo(
+    item: Item,
+    raid: Raid,
+    chest: ChestType,
+    quick_entry_key: Option<Key>,
+    honors: &PBHLHonors,
+    settings: &mut AppSettings,
+    ui: &mut Ui,
+) {
     let mut label_text = match item {
         Item::NoDrop => "No Drop",
         Item::HollowKey => "Hollow Key",
@@ -410,125 +924,136 @@ pub fn place_image_button_combo(
             _ => "",
         };
     }
-    let mut item_image = match item {
-        Item::NoDrop => NO_BLUE_CHEST,
-        Item::HollowKey => HOLLOW_KEY,
-        Item::VerdantAzurite => VERDANT_AZURITE,
-        Item::SilverCentrum => SILVER_CENTRUM,
-        Item::GoldBrick => GOLD_BAR,
-        Item::ChampionMerit => C_MERIT,
-        Item::SupremeMerit => S_MERIT,
-        Item::LegendaryMerit => L_MERIT,
-        Item::CoronationRing => C_RING,
-        Item::LineageRing => L_RING,
-        Item::IntricacyRing => I_RING,
-        Item::WeaponPlusMark1 => P_MARK_1,
-        Item::WeaponPlusMark2 => P_MARK_2,
-        Item::WeaponPlusMark3 => P_MARK_3,
-        Item::BrimstoneEarrings => FIRE_EARRING,
-        Item::PermafrostEarrings => WATER_EARRING,
-        Item::BrickearthEarrings => EARTH_EARRING,
-        Item::JetstreamEarrings => WIND_EARRING,
-        Item::NightshadeEarrings => DARK_EARRING,
-        Item::SunbeamEarrings => LIGHT_EARRING,
-        Item::EternitySand => ETERNITY_SAND,
-    };
-    if chest == ChestType::Host {
-        if raid == Raid::Huanglong {
-            item_image = HL_HOST_BAR;
+    ui.spacing_mut().item_spacing.x = 3.;
+    let ticked = settings.kill.contains(&(item, raid, chest));
+    if settings.app_settings.button_label_combo[1] {
+        let response = ui.add(
+            CustomImageButton::new(
+                textures::item_texture(ui.ctx(), item, raid, chest),
+                (32., 32.),
+            )
+            .selected(ticked),
+        );
+        if let Some(key) = quick_entry_key {
+            hotkeys::paint_key_badge(ui, response.rect, key);
         }
-        if raid == Raid::Qilin {
-            item_image = QL_HOST_BAR;
+        let response =
+            response.context_menu(|ui| place_batch_menu(item, raid, chest, honors, settings, ui));
+        if response.clicked() {
+            let shift = ui.input().modifiers.shift_only();
+            if shift {
+                settings.remove_last_drop(item, raid, chest);
+            } else if !shift {
+                settings.click_item(item, raid, chest, honors);
+            }
         }
-        if raid == Raid::HLQL {
-            item_image = HLQL_HOST_BAR;
+    }
+    if settings.app_settings.button_label_combo[0] {
+        let mut button = widgets::Button::new(label_text);
+        if ticked {
+            button = button.fill(ui.visuals().selection.bg_fill);
         }
-        if raid == Raid::UBHL {
-            item_image = UBHL_HOST_BAR;
+        let response = ui.add(button);
+        // The icon already shows the badge when both are visible.
+        if let (Some(key), false) = (quick_entry_key, settings.app_settings.button_label_combo[1]) {
+            hotkeys::paint_key_badge(ui, response.rect, key);
         }
-        if raid == Raid::PBHL {
-            item_image = PBHL_HOST_BAR;
+        let response =
+            response.context_menu(|ui| place_batch_menu(item, raid, chest, honors, settings, ui));
+        if response.clicked() {
+            let shift = ui.input().modifiers.shift_only();
+            if shift {
+                settings.remove_last_drop(item, raid, chest);
+            } else if !shift {
+                settings.click_item(item, raid, chest, honors);
+            }
         }
     }
-    if chest == ChestType::Flip {
-        if raid == Raid::UBHL {
-            item_image = UBHL_FLIP_BAR;
+    if settings.app_settings.active_items_2[26] {
+        let drop_count = settings.stats.item_count(raid, item, chest);
+        ui.label("x".to_string() + &drop_count.to_string());
+    }
+}
+
+const MAX_BATCH_KILLS: u32 = 999;
+const MAX_DROP_QUANTITY: u32 = 99;
+
+/// The drops ticked for the kill being logged along with buttons to log or clear them.
+pub fn place_kill_record(settings: &mut AppSettings, honors: &PBHLHonors, ui: &mut Ui) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label("This Kill: ");
+        if settings.kill.is_empty() {
+            ui.label(RichText::new("click everything that dropped").weak());
         }
-        if raid == Raid::Xeno {
-            item_image = XENO_FLIP_BAR;
+        for (item, raid, chest) in &settings.kill {
+            ui.label(format!("{} ({} {})", item, raid, chest));
         }
-    }
-    ui.spacing_mut().item_spacing.x = 3.;
-    let local_last_added_drop = &settings
-        .droplog
-        .drop
-        .iter()
-        .rposition(|x| x.item == item && x.raid == raid && x.chest == chest)
-        .unwrap_or_default();
-    if settings.app_settings.button_label_combo[1]
-        && ui
-            .add(CustomImageButton::new(
-                &ui.ctx()
-                    .load_texture(image_item_name, load_image_from_memory(item_image).unwrap()),
-                (32., 32.),
-            ))
+        let any_ticked = !settings.kill.is_empty();
+        if ui
+            .add_enabled(any_ticked, widgets::Button::new("Log Kill"))
             .clicked()
-    {
-        let shift = ui.input().modifiers.shift_only();
-        if shift {
-            if settings
-                .droplog
-                .drop
-                .iter()
-                .filter(|x| x.item == item && x.raid == raid && x.chest == chest)
-                .count()
-                > 0
-            {
-                let _ = settings.droplog.drop.remove(*local_last_added_drop);
-            }
-        } else if !shift {
-            let _ = settings.droplog.drop.push(ItemDrop::new(
-                settings.droplog.drop.clone().len().try_into().unwrap(),
-                get_time(),
-                raid,
-                item,
-                chest,
-                Some(format!("{}", honors)),
-            ));
+        {
+            settings.log_kill(honors);
         }
-    }
-    if settings.app_settings.button_label_combo[0] && ui.button(label_text).clicked() {
-        let shift = ui.input().modifiers.shift_only();
-        if shift {
-            if settings
-                .droplog
-                .drop
-                .iter()
-                .filter(|x| x.item == item && x.raid == raid && x.chest == chest)
-                .count()
-                > 0
-            {
-                let _ = settings.droplog.drop.remove(*local_last_added_drop);
-            }
-        } else if !shift {
-            let _ = settings.droplog.drop.push(ItemDrop::new(
-                settings.droplog.drop.clone().len().try_into().unwrap(),
-                get_time(),
-                raid,
-                item,
-                chest,
-                Some(format!("{}", honors)),
-            ));
+        if ui
+            .add_enabled(any_ticked, widgets::Button::new("Clear"))
+            .clicked()
+        {
+            settings.kill.clear();
+        }
+    });
+}
+
+/// Kills and amount per kill entered in an item's "Add Several" menu.
+#[derive(Clone, Copy)]
+struct BatchEntry {
+    kills: u32,
+    quantity: u32,
+}
+
+impl Default for BatchEntry {
+    fn default() -> Self {
+        Self {
+            kills: 1,
+            quantity: 1,
         }
     }
-    if settings.app_settings.active_items_2[26] {
-        let drop_count = settings
-            .droplog
-            .drop
-            .iter()
-            .filter(|x| x.item == item && x.raid == raid && x.chest == chest)
-            .count();
-        ui.label("x".to_string() + &drop_count.to_string());
+}
+
+/// Right-click menu of an item's button for logging several kills at once, such as a long
+/// full-auto session, and how many of the item each kill dropped if it drops in a quantity.
+pub fn place_batch_menu(
+    item: Item,
+    raid: Raid,
+    chest: ChestType,
+    honors: &PBHLHonors,
+    settings: &mut AppSettings,
+    ui: &mut Ui,
+) {
+    let id = ui.make_persistent_id(format!("batch_entry_{:?}_{:?}_{:?}", item, raid, chest));
+    let mut batch: BatchEntry = ui.data().get_temp(id).unwrap_or_default();
+    ui.label(format!("Add Several {}", item));
+    ui.horizontal(|ui| {
+        ui.label("Kills: ");
+        ui.add(DragValue::new(&mut batch.kills).clamp_range(1..=MAX_BATCH_KILLS));
+    });
+    if item.drops_in_quantity() {
+        ui.horizontal(|ui| {
+            ui.label("Amount Each: ");
+            ui.add(DragValue::new(&mut batch.quantity).clamp_range(1..=MAX_DROP_QUANTITY));
+        });
+    }
+    if ui.button("Add").clicked() {
+        let quantity = if item.drops_in_quantity() {
+            batch.quantity
+        } else {
+            1
+        };
+        settings.log_drops(item, raid, chest, honors, batch.kills, quantity);
+        ui.data().remove::<BatchEntry>(id);
+        ui.close_menu();
+    } else {
+        ui.data().insert_temp(id, batch);
     }
 }
 
@@ -538,43 +1063,156 @@ pub fn create_path(path: &str) -> std::io::Result<()> {
     Ok(())
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn export(droplog: DropLog) -> Result<(), Box<dyn Error>> {
-    let logged_drops = droplog.drop.len().to_string();
-    let export_time: DateTime<Local> = Local::now();
-    let export_four_digit_year = export_time.format("%Y").to_string();
-    let export_month = export_time.format("%m").to_string();
-    let export_day = export_time.format("%d").to_string();
-    if !Path::new("./exports/").exists() {
-        create_path("./exports/")?;
-    }
-    let str_path = format!(
-        "./exports/dorothy-{}-drops-{}-{}-{}.csv",
-        &logged_drops, &export_four_digit_year, &export_month, &export_day
-    );
-
-    if Path::new("./exports/").exists() {
-        let file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .append(true)
-            .open(&str_path)
-            .unwrap();
-        let mut wtr = csv::Writer::from_writer(&file);
-
-        for drop in droplog.drop {
-            wtr.serialize(drop)?;
-        }
-        wtr.flush()?;
-    }
-    Ok(())
-}
-
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub version: u8,
     pub app_settings: DorothyConfig,
     pub droplog: DropLog,
+    #[serde(default)]
+    pub roll_log: pulls::RollLog,
+    /// Drops ticked for the kill being logged when logging by kill.
+    #[serde(skip)]
+    pub kill: Vec<(Item, Raid, ChestType)>,
+    /// Totals of `droplog`, see `update_stats`.
+    #[serde(skip)]
+    pub stats: stats::DropIndex,
+    /// Ids of the drops logged last, which Undo removes together.
+    #[serde(skip)]
+    last_logged: std::ops::Range<u32>,
+}
+
+impl AppSettings {
+    pub fn log_drop(&mut self, item: Item, raid: Raid, chest: ChestType, honors: &PBHLHonors) {
+        self.log_drops(item, raid, chest, honors, 1, 1);
+    }
+    /// What clicking an item's button does: logs it, or ticks it for the kill being logged when
+    /// logging by kill.
+    pub fn click_item(&mut self, item: Item, raid: Raid, chest: ChestType, honors: &PBHLHonors) {
+        if !self.app_settings.log_by_kill {
+            self.log_drop(item, raid, chest, honors);
+            return;
+        }
+        match self.kill.iter().position(|x| *x == (item, raid, chest)) {
+            Some(pos) => {
+                self.kill.remove(pos);
+            }
+            None => self.kill.push((item, raid, chest)),
+        }
+    }
+    /// Logs every ticked drop as a single kill.
+    pub fn log_kill(&mut self, honors: &PBHLHonors) {
+        let kill_id = self.stats.next_kill_id();
+        let date_obtained = get_time();
+        let first_id = self.stats.next_drop_id();
+        let mut drop_id = first_id;
+        for (item, raid, chest) in std::mem::take(&mut self.kill) {
+            self.droplog.drop.push(
+                ItemDrop::new(
+                    drop_id,
+                    date_obtained.clone(),
+                    raid,
+                    item,
+                    chest,
+                    Some(format!("{}", honors)),
+                )
+                .with_kill_id(kill_id),
+            );
+            drop_id += 1;
+        }
+        self.last_logged = first_id..drop_id;
+        self.update_stats();
+    }
+    /// Logs `kills` drops of `quantity` pieces each. They share a timestamp since they're logged
+    /// together after the fact.
+    pub fn log_drops(
+        &mut self,
+        item: Item,
+        raid: Raid,
+        chest: ChestType,
+        honors: &PBHLHonors,
+        kills: u32,
+        quantity: u32,
+    ) {
+        let date_obtained = get_time();
+        let first_id = self.stats.next_drop_id();
+        for drop_id in first_id..first_id + kills {
+            self.droplog.drop.push(
+                ItemDrop::new(
+                    drop_id,
+                    date_obtained.clone(),
+                    raid,
+                    item,
+                    chest,
+                    Some(format!("{}", honors)),
+                )
+                .with_quantity(quantity),
+            );
+        }
+        self.last_logged = first_id..first_id + kills;
+        self.update_stats();
+    }
+
+    /// Removes the most recent drop of `item` from `raid`'s `chest`, the same as a Shift+Click.
+    pub fn remove_last_drop(&mut self, item: Item, raid: Raid, chest: ChestType) {
+        if let Some(last_added_drop) = self
+            .droplog
+            .drop
+            .iter()
+            .rposition(|x| x.item == item && x.raid == raid && x.chest == chest)
+        {
+            self.droplog.drop.remove(last_added_drop);
+            self.update_stats();
+        }
+    }
+
+    /// Removes the drop with `drop_id`, the same as clicking it in Recent Drops. Logs from older
+    /// versions can have several drops with the same id, of which only the latest is removed.
+    pub fn remove_drop(&mut self, drop_id: u32) {
+        if let Some(pos) = self.droplog.drop.iter().rposition(|x| x.drop_id == drop_id) {
+            self.droplog.drop.remove(pos);
+            self.update_stats();
+        }
+    }
+
+    /// Removes what was logged last: every drop of the last kill, or of the last batch logged at
+    /// once.
+    pub fn undo_last_drop(&mut self) {
+        let (batch, kill_id) = match self.droplog.drop.last() {
+            Some(last) if self.last_logged.contains(&last.drop_id) => {
+                (self.last_logged.clone(), last.kill_id)
+            }
+            Some(last) => (last.drop_id..last.drop_id + 1, last.kill_id),
+            None => return,
+        };
+        let kept = self
+            .droplog
+            .drop
+            .iter()
+            .rposition(|x| !batch.contains(&x.drop_id) && (kill_id.is_none() || x.kill_id != kill_id))
+            .map_or(0, |pos| pos + 1);
+        self.droplog.drop.truncate(kept);
+        self.last_logged = 0..0;
+        self.update_stats();
+    }
+
+    pub fn reset_drops(&mut self) {
+        self.droplog.drop = DropLog::reset();
+        self.update_stats();
+    }
+
+    /// Adds previously exported drops to the log, see `DropLog::import`.
+    pub fn import_drops(&mut self, drops: Vec<ItemDrop>) -> usize {
+        let imported = self.droplog.import(drops);
+        // Older drops land in the middle of the log, which `update_stats` wouldn't see
+        self.stats = stats::DropIndex::new(&self.droplog.drop);
+        imported
+    }
+
+    /// Brings `stats` up to date with the drop log. Removing drops rebuilds it, so anything that
+    /// removes drops has to call this before logging more.
+    pub fn update_stats(&mut self) {
+        self.stats.update(&self.droplog.drop);
+    }
 }
 
 #[derive(PartialEq, Default, Debug, Serialize, Deserialize)]
@@ -617,7 +1255,7 @@ pub enum UiTab {
     #[default]
     None,
 }
-#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Default, Debug, Serialize, Deserialize)]
 pub enum Raid {
     Akasha,
     PBHL,
@@ -652,6 +1290,42 @@ pub enum Raid {
     None,
 }
 
+impl Raid {
+    /// Share links store positions in this list, so new raids go at the end.
+    pub const ALL: [Raid; 30] = [
+        Raid::Akasha,
+        Raid::PBHL,
+        Raid::GOHL,
+        Raid::UBHL,
+        Raid::Xeno,
+        Raid::Huanglong,
+        Raid::Qilin,
+        Raid::HLQL,
+        Raid::Wilnas,
+        Raid::Wamdus,
+        Raid::Galleon,
+        Raid::Ewiyar,
+        Raid::LuWoh,
+        Raid::Fediel,
+        Raid::Atum,
+        Raid::Tefnut,
+        Raid::Bennu,
+        Raid::Ra,
+        Raid::Horus,
+        Raid::Osiris,
+        Raid::TiamatMalice,
+        Raid::LeviathanMalice,
+        Raid::Phronesis,
+        Raid::LuminieraMalice,
+        Raid::AnimaAnimusCore,
+        Raid::Mugen,
+        Raid::Diaspora,
+        Raid::Siegfried,
+        Raid::SUB,
+        Raid::None,
+    ];
+}
+
 impl fmt::Display for Raid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
@@ -689,7 +1363,7 @@ impl fmt::Display for Raid {
     }
 }
 
-#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Default, Debug, Serialize, Deserialize)]
 pub enum ChestType {
     Host,
     Mvp,
@@ -700,6 +1374,18 @@ pub enum ChestType {
     Gold,
 }
 
+impl ChestType {
+    /// Share links store positions in this list, so new chests go at the end.
+    pub const ALL: [ChestType; 6] = [
+        ChestType::Host,
+        ChestType::Mvp,
+        ChestType::Flip,
+        ChestType::None,
+        ChestType::Blue,
+        ChestType::Gold,
+    ];
+}
+
 impl fmt::Display for ChestType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
@@ -713,7 +1399,7 @@ impl fmt::Display for ChestType {
     }
 }
 
-#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Default, Debug, Serialize, Deserialize)]
 pub enum Item {
     VerdantAzurite,
     HollowKey,
@@ -739,6 +1425,54 @@ pub enum Item {
     NoDrop,
 }
 
+impl Item {
+    /// Share links store positions in this list, so new items go at the end.
+    pub const ALL: [Item; 21] = [
+        Item::VerdantAzurite,
+        Item::HollowKey,
+        Item::ChampionMerit,
+        Item::SupremeMerit,
+        Item::LegendaryMerit,
+        Item::SilverCentrum,
+        Item::WeaponPlusMark1,
+        Item::WeaponPlusMark2,
+        Item::WeaponPlusMark3,
+        Item::CoronationRing,
+        Item::LineageRing,
+        Item::IntricacyRing,
+        Item::GoldBrick,
+        Item::BrimstoneEarrings,
+        Item::PermafrostEarrings,
+        Item::BrickearthEarrings,
+        Item::JetstreamEarrings,
+        Item::SunbeamEarrings,
+        Item::NightshadeEarrings,
+        Item::EternitySand,
+        Item::NoDrop,
+    ];
+
+    /// The elemental earrings, highlighted together by the default Earrings rule.
+    pub fn is_earring(&self) -> bool {
+        matches!(
+            self,
+            Item::BrimstoneEarrings
+                | Item::PermafrostEarrings
+                | Item::BrickearthEarrings
+                | Item::JetstreamEarrings
+                | Item::SunbeamEarrings
+                | Item::NightshadeEarrings
+        )
+    }
+
+    /// Items a single kill can drop several of.
+    pub fn drops_in_quantity(&self) -> bool {
+        matches!(
+            self,
+            Item::EternitySand | Item::ChampionMerit | Item::SupremeMerit | Item::LegendaryMerit
+        )
+    }
+}
+
 impl fmt::Display for Item {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
@@ -794,6 +1528,16 @@ pub struct ItemDrop {
     chest: ChestType,
     #[serde(default)]
     honors: Option<String>,
+    /// How many of the item dropped at once, for items like Eternity Sand and merits.
+    #[serde(default = "default_quantity")]
+    quantity: u32,
+    /// Drops logged together as one kill share an id.
+    #[serde(default)]
+    kill_id: Option<u32>,
+}
+
+fn default_quantity() -> u32 {
+    1
 }
 
 #[allow(dead_code)]
@@ -813,6 +1557,36 @@ impl ItemDrop {
             item,
             chest,
             honors,
+            quantity: 1,
+            kill_id: None,
+        }
+    }
+
+    pub fn with_quantity(mut self, quantity: u32) -> Self {
+        self.quantity = quantity.max(1);
+        self
+    }
+
+    pub fn with_kill_id(mut self, kill_id: u32) -> Self {
+        self.kill_id = Some(kill_id);
+        self
+    }
+
+    /// Just what dropped and in which kill, for tests that don't look at anything else.
+    #[cfg(test)]
+    pub(crate) fn of(raid: Raid, item: Item, chest: ChestType, kill_id: Option<u32>) -> Self {
+        Self {
+            kill_id,
+            ..Self::new(0, String::new(), raid, item, chest, None)
+        }
+    }
+
+    /// Item as shown in Recent Drops, with the amount when several dropped at once.
+    pub fn label(&self) -> String {
+        if self.quantity > 1 {
+            format!("{} x{}", self.item, self.quantity)
+        } else {
+            format!("{}", self.item)
         }
     }
 }
@@ -822,6 +1596,9 @@ pub struct DorothyConfig {
     pub left_panel_visible: bool,
     pub right_panel_visible: bool,
     pub move_right_to_bottom: bool,
+    /// Show Recent Drops as a grid of icons instead of a list.
+    #[serde(default)]
+    pub recent_drops_grid: bool,
     pub always_on_top: bool,
     pub reset_on_export: bool,
     pub droprate_by_kills: bool,
@@ -829,21 +1606,55 @@ pub struct DorothyConfig {
     pub toggle_active_items: bool,
     pub vertical_grid: bool,
     pub auto_update_enabled: bool,
-    pub auto_update_status: u8,
     pub grid_spacing_x: f32,
     pub grid_spacing_y: f32,
     pub ui_scale: f32,
     pub body_font_size: f32,
+    /// Items hidden before tabs had layouts, kept to lay out tabs that haven't been edited since.
     pub active_items: [bool; 32],
     pub active_items_2: [bool; 32],
     pub button_label_combo: [bool; 2],
-    pub crystals_amount: String,
-    pub ten_pulls_amount: String,
-    pub single_pulls_amount: String,
-    pub shrimp_amount: String,
-    pub total_pulls: String,
+    #[serde(default, deserialize_with = "pulls::amount_from_number_or_text")]
+    pub crystals_amount: u32,
+    #[serde(default, deserialize_with = "pulls::amount_from_number_or_text")]
+    pub ten_pulls_amount: u32,
+    #[serde(default, deserialize_with = "pulls::amount_from_number_or_text")]
+    pub single_pulls_amount: u32,
+    #[serde(default, deserialize_with = "pulls::amount_from_number_or_text")]
+    pub shrimp_amount: u32,
     #[serde(default)]
     pub current_ui_tab: UiTab,
+    #[serde(default)]
+    pub export_format: ExportFormat,
+    #[serde(default)]
+    pub export_filter: ExportFilter,
+    #[serde(default)]
+    pub import_presets: Vec<import::ImportMapping>,
+    #[serde(default)]
+    pub spark_plan: pulls::SparkPlan,
+    #[serde(default)]
+    pub odds_settings: gacha::OddsSettings,
+    #[serde(default)]
+    pub hotkeys: hotkeys::Keybindings,
+    #[serde(default)]
+    pub quick_entry: bool,
+    #[serde(default)]
+    pub overlay: overlay::OverlaySettings,
+    #[serde(default)]
+    pub log_by_kill: bool,
+    /// Name of the custom theme in use. The built-in dark or light theme is used without one.
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub themes: Vec<theme::Theme>,
+    #[serde(default = "highlight::default_rules")]
+    pub highlight_rules: Vec<highlight::HighlightRule>,
+    /// Layout of every tab with item buttons, see `migrate_item_layouts`.
+    #[serde(default)]
+    pub item_layouts: Vec<layout::TabLayout>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(default)]
+    pub update_source: updater::UpdateSettings,
 }
 
 #[allow(dead_code)]
@@ -854,6 +1665,7 @@ impl Default for DorothyConfig {
             left_panel_visible: true,
             right_panel_visible: true,
             move_right_to_bottom: false,
+            recent_drops_grid: false,
             always_on_top: false,
             reset_on_export: true,
             droprate_by_kills: false,
@@ -861,7 +1673,6 @@ impl Default for DorothyConfig {
             toggle_active_items: false,
             vertical_grid: false,
             auto_update_enabled: true,
-            auto_update_status: 0,
             grid_spacing_x: 10.,
             grid_spacing_y: 20.,
             ui_scale: 1.,
@@ -870,11 +1681,25 @@ impl Default for DorothyConfig {
             active_items_2: [true; 32],
             button_label_combo: [true; 2],
             current_ui_tab: UiTab::Akasha,
-            crystals_amount: "0".to_string(),
-            ten_pulls_amount: "0".to_string(),
-            single_pulls_amount: "0".to_string(),
-            shrimp_amount: "0".to_string(),
-            total_pulls: "".to_string(),
+            crystals_amount: 0,
+            ten_pulls_amount: 0,
+            single_pulls_amount: 0,
+            shrimp_amount: 0,
+            export_format: ExportFormat::Csv,
+            export_filter: ExportFilter::default(),
+            import_presets: vec![],
+            spark_plan: pulls::SparkPlan::default(),
+            odds_settings: gacha::OddsSettings::default(),
+            hotkeys: hotkeys::Keybindings::default(),
+            quick_entry: false,
+            overlay: overlay::OverlaySettings::default(),
+            log_by_kill: false,
+            theme: None,
+            themes: vec![],
+            highlight_rules: highlight::default_rules(),
+            item_layouts: vec![],
+            #[cfg(not(target_arch = "wasm32"))]
+            update_source: updater::UpdateSettings::default(),
         }
     }
 }
@@ -887,6 +1712,7 @@ impl DorothyConfig {
             left_panel_visible: true,
             right_panel_visible: false,
             move_right_to_bottom: false,
+            recent_drops_grid: false,
             always_on_top: false,
             reset_on_export: true,
             droprate_by_kills: false,
@@ -894,7 +1720,6 @@ impl DorothyConfig {
             toggle_active_items: false,
             vertical_grid: false,
             auto_update_enabled: true,
-            auto_update_status: 0,
             grid_spacing_x: 10.,
             grid_spacing_y: 20.,
             ui_scale: 1.,
@@ -903,11 +1728,25 @@ impl DorothyConfig {
             active_items_2: [true; 32],
             button_label_combo: [true; 2],
             current_ui_tab: UiTab::Akasha,
-            crystals_amount: "0".to_string(),
-            ten_pulls_amount: "0".to_string(),
-            single_pulls_amount: "0".to_string(),
-            shrimp_amount: "0".to_string(),
-            total_pulls: "".to_string(),
+            crystals_amount: 0,
+            ten_pulls_amount: 0,
+            single_pulls_amount: 0,
+            shrimp_amount: 0,
+            export_format: ExportFormat::Csv,
+            export_filter: ExportFilter::default(),
+            import_presets: vec![],
+            spark_plan: pulls::SparkPlan::default(),
+            odds_settings: gacha::OddsSettings::default(),
+            hotkeys: hotkeys::Keybindings::default(),
+            quick_entry: false,
+            overlay: overlay::OverlaySettings::default(),
+            log_by_kill: false,
+            theme: None,
+            themes: vec![],
+            highlight_rules: highlight::default_rules(),
+            item_layouts: vec![],
+            #[cfg(not(target_arch = "wasm32"))]
+            update_source: updater::UpdateSettings::default(),
         }
     }
 }
@@ -24,6 +24,8 @@ fn main() {
     let app = AppDorothy::default();
     let native_options = eframe::NativeOptions {
         icon_data: Some(app_icon),
+        // Lets the compact overlay fade into the desktop behind it.
+        transparent: true,
         ..Default::default()
     };
     eframe::run_native(Box::new(app), native_options);
@@ -0,0 +1,198 @@
+use crate::{
+    place_batch_menu, place_kill_record, tab_buttons, tab_raid, textures, AppSettings,
+    CustomImageButton, Item, PBHLHonors, Raid,
+};
+use eframe::{
+    egui::{self, Color32, RichText, Sense, Ui, Vec2},
+    epi,
+};
+use serde::{Deserialize, Serialize};
+
+const OVERLAY_COLUMNS: usize = 5;
+const CELL_WIDTH: f32 = 44.;
+const CELL_HEIGHT: f32 = 64.;
+const HEADER_HEIGHT: f32 = 30.;
+const MARGIN: f32 = 8.;
+const MIN_WIDTH: f32 = 180.;
+const KILL_RECORD_HEIGHT: f32 = 48.;
+
+/// Settings of the compact overlay Dorothy shrinks into while farming.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlaySettings {
+    pub enabled: bool,
+    /// Opacity of the overlay's background from 0.2 to 1. Only the desktop app can see through it.
+    pub opacity: f32,
+    /// Size of the full window before the overlay was turned on, restored when it's turned off.
+    pub window_size: Option<[f32; 2]>,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            opacity: 1.,
+            window_size: None,
+        }
+    }
+}
+
+/// Window size that fits `buttons` item icons in rows of five, and the kill being logged when
+/// logging by kill, in points.
+pub fn overlay_size(buttons: usize, log_by_kill: bool) -> Vec2 {
+    let columns = buttons.clamp(1, OVERLAY_COLUMNS);
+    let rows = (0..buttons).step_by(OVERLAY_COLUMNS).count().max(1);
+    let kill_record_height = if log_by_kill { KILL_RECORD_HEIGHT } else { 0. };
+    Vec2::new(
+        (columns as f32 * CELL_WIDTH + MARGIN * 2.).max(MIN_WIDTH),
+        HEADER_HEIGHT + rows as f32 * CELL_HEIGHT + kill_record_height + MARGIN * 2.,
+    )
+}
+
+/// Whether Dorothy has already turned the window into the overlay and how big it made it.
+#[derive(Default)]
+pub struct OverlayWindow {
+    active: bool,
+    size: Option<Vec2>,
+}
+
+impl OverlayWindow {
+    /// Removes the window's decorations and shrinks it to fit `buttons` icons when the overlay is
+    /// turned on or its contents change, and puts the window back when it's turned off.
+    pub fn sync(
+        &mut self,
+        settings: &mut OverlaySettings,
+        buttons: usize,
+        log_by_kill: bool,
+        screen_size: Vec2,
+        frame: &epi::Frame,
+    ) {
+        if settings.enabled {
+            if !self.active {
+                self.active = true;
+                // Dorothy may have been closed as an overlay, in which case the window is
+                // already overlay sized and the size from before is kept.
+                if settings.window_size.is_none() {
+                    settings.window_size = Some([screen_size.x, screen_size.y]);
+                }
+                frame.set_decorations(false);
+            }
+            let size = overlay_size(buttons, log_by_kill);
+            if self.size != Some(size) {
+                self.size = Some(size);
+                frame.set_window_size(size);
+            }
+        } else if self.active {
+            self.active = false;
+            self.size = None;
+            frame.set_decorations(true);
+            if let Some([width, height]) = settings.window_size.take() {
+                frame.set_window_size(Vec2::new(width, height));
+            }
+        }
+    }
+}
+
+/// Background of the overlay, faded to `opacity`.
+pub fn overlay_frame(style: &egui::Style, opacity: f32) -> egui::Frame {
+    egui::Frame::none()
+        .fill(
+            style
+                .visuals
+                .window_fill()
+                .linear_multiply(opacity.clamp(0.2, 1.)),
+        )
+        .margin(Vec2::splat(MARGIN))
+}
+
+/// The current tab's items with their counts and dry streaks under a header with the raid's kills.
+/// Clicking an item logs it and Shift-clicking removes the last one, like the full window's buttons.
+/// Dragging the header moves the window. Returns `true` when the overlay should be closed.
+pub fn place_overlay(
+    settings: &mut AppSettings,
+    selected_raid: Raid,
+    honors: &PBHLHonors,
+    frame: &epi::Frame,
+    ui: &mut Ui,
+) -> bool {
+    let mut close = false;
+    let raid = tab_raid(&settings.app_settings.current_ui_tab, selected_raid);
+    ui.horizontal(|ui| {
+        let header = ui
+            .add(
+                egui::Label::new(
+                    RichText::new(format!(
+                        "{}: {} kills",
+                        raid,
+                        settings.stats.total_kills(raid)
+                    ))
+                    .strong(),
+                )
+                .sense(Sense::drag()),
+            )
+            .on_hover_text("Drag to move the overlay");
+        if header.drag_started() {
+            frame.drag_window();
+        }
+        ui.with_layout(egui::Layout::right_to_left(), |ui| {
+            if ui
+                .small_button("✖")
+                .on_hover_text("Back to the full window")
+                .clicked()
+            {
+                close = true;
+            }
+        });
+    });
+
+    let buttons = tab_buttons(&settings.app_settings, selected_raid);
+    if buttons.is_empty() {
+        ui.label("Nothing to log on this tab.");
+        return close;
+    }
+    egui::Grid::new("overlay_grid")
+        .min_col_width(CELL_WIDTH - ui.spacing().item_spacing.x)
+        .show(ui, |ui| {
+            for (pos, (item, raid, chest)) in buttons.into_iter().enumerate() {
+                if pos > 0 && pos % OVERLAY_COLUMNS == 0 {
+                    ui.end_row();
+                }
+                ui.vertical_centered(|ui| {
+                    let texture = textures::item_texture(ui.ctx(), item, raid, chest);
+                    let count = settings.stats.item_count(raid, item, chest);
+                    let response = ui
+                        .add(CustomImageButton::new(texture, (32., 32.)))
+                        .on_hover_text(format!("{} from {}", item, raid))
+                        .context_menu(|ui| {
+                            place_batch_menu(item, raid, chest, honors, settings, ui)
+                        });
+                    if response.clicked() {
+                        if ui.input().modifiers.shift_only() {
+                            settings.remove_last_drop(item, raid, chest);
+                        } else {
+                            settings.click_item(item, raid, chest, honors);
+                        }
+                    }
+                    ui.label(RichText::new(format!("x{}", count)).small());
+                    if item != Item::NoDrop {
+                        let dry = settings.stats.dry_streak(raid, item);
+                        ui.label(RichText::new(format!("{} dry", dry)).small().weak())
+                            .on_hover_text("Kills since this last dropped");
+                    }
+                });
+            }
+        });
+    if settings.app_settings.log_by_kill {
+        place_kill_record(settings, honors, ui);
+    }
+    close
+}
+
+/// Color the window is cleared to, which shows through a faded overlay on the desktop app.
+pub fn clear_color(settings: &OverlaySettings) -> egui::Rgba {
+    if settings.enabled {
+        egui::Rgba::TRANSPARENT
+    } else {
+        Color32::from_rgb(12, 12, 12).into()
+    }
+}
@@ -0,0 +1,531 @@
+use crate::{format_rate, get_percentage, PullBreakdown};
+use chrono::{Duration, NaiveDate};
+use eframe::egui::{self, Color32, Ui};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+pub const SPARK_PULLS: u32 = 300;
+pub const CRYSTALS_PER_PULL: u32 = 300;
+/// Projections stop here rather than looking for a spark that never comes.
+const MAX_PROJECTED_DAYS: i64 = 3650;
+
+/// Reads an amount typed into the pull calculator, allowing thousands separators.
+/// Empty text is 0; anything else that isn't a whole number is `None`.
+pub fn parse_amount(text: &str) -> Option<u32> {
+    let amount = text.trim().replace(',', "");
+    if amount.is_empty() {
+        return Some(0);
+    }
+    amount.parse::<u32>().ok()
+}
+
+/// Pull calculator amounts used to be saved as text. Reads either, treating text that isn't
+/// a whole number as 0 like the calculator did.
+pub fn amount_from_number_or_text<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Amount {
+        Number(u32),
+        Text(String),
+    }
+    Ok(match Amount::deserialize(deserializer)? {
+        Amount::Number(amount) => amount,
+        Amount::Text(amount) => parse_amount(&amount).unwrap_or_default(),
+    })
+}
+
+/// Text typed into the pull calculator. `None` until it's first shown with the saved amount.
+#[derive(Default)]
+pub struct PullInputs {
+    pub crystals: Option<String>,
+    pub ten_pull_tickets: Option<String>,
+    pub single_tickets: Option<String>,
+    pub shrimps: Option<String>,
+}
+
+/// Text field for a pull calculator amount. `amount` only changes while the text is valid.
+pub fn place_amount_input(label: &str, amount: &mut u32, text: &mut Option<String>, ui: &mut Ui) {
+    let text = text.get_or_insert_with(|| amount.to_string());
+    ui.horizontal(|ui| {
+        ui.label(label);
+        if ui.add(egui::TextEdit::singleline(text)).changed() {
+            if let Some(parsed) = parse_amount(text) {
+                *amount = parsed;
+            }
+        }
+        if parse_amount(text).is_none() {
+            ui.colored_label(Color32::from_rgb(255, 90, 90), "Should be a whole number");
+        }
+    });
+}
+
+pub fn place_pull_breakdown(breakdown: &PullBreakdown, ui: &mut Ui) {
+    ui.label(format!("Pulls from Crystals: {}", breakdown.crystal_pulls));
+    ui.label(format!("Pulls from Tickets: {}", breakdown.ticket_pulls));
+    ui.label(format!("Pulls from Ebi Fry: {}", breakdown.shrimp_pulls));
+    ui.label(format!(
+        "Leftover Crystals: {}",
+        breakdown.leftover_crystals
+    ));
+    ui.add_space(5.);
+    ui.label(format!(
+        "Total: {} pulls ({})",
+        breakdown.total(),
+        get_percentage(breakdown.total() as f32, SPARK_PULLS as f32)
+    ));
+}
+
+/// What the Spark Planner expects to earn between today and `target_date`.
+#[derive(PartialEq, Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SparkPlan {
+    /// `YYYY-MM-DD`, e.g. the first day of the next Legfest.
+    pub target_date: String,
+    /// Average crystals earned a day from login bonuses, events, missions etc.
+    pub daily_crystals: u32,
+    /// One-off crystals expected before the target date, e.g. an anniversary or a new event.
+    pub bonus_crystals: u32,
+    /// Free pulls a day during a free draw campaign such as Draw Dash.
+    pub daily_free_pulls: u32,
+    /// Days of the free draw campaign left before the target date.
+    pub free_pull_days: u32,
+}
+
+/// Where a spark stands on the target date according to a `SparkPlan`.
+#[derive(PartialEq, Clone, Debug)]
+pub struct SparkProjection {
+    pub days_left: i64,
+    pub pulls_on_target_date: u32,
+    /// First day with enough pulls for a spark, or `None` if that's more than ten years away.
+    pub spark_date: Option<NaiveDate>,
+    /// Crystals short of a spark on the target date. 0 once the spark is reached.
+    pub crystals_needed: u32,
+}
+
+impl SparkPlan {
+    /// Crystals and free pulls earned by `days` days from now.
+    fn income(&self, days: i64) -> (u32, u32) {
+        let days = days.max(0) as u32;
+        let crystals = self
+            .daily_crystals
+            .saturating_mul(days)
+            .saturating_add(self.bonus_crystals);
+        let free_pulls = self
+            .daily_free_pulls
+            .saturating_mul(days.min(self.free_pull_days));
+        (crystals, free_pulls)
+    }
+
+    /// Projects `crystals` plus `ticket_pulls` (tickets and other pulls that aren't crystals)
+    /// forward to the target date. Returns `None` if the target date isn't a valid date.
+    pub fn project(
+        &self,
+        crystals: u32,
+        ticket_pulls: u32,
+        today: NaiveDate,
+    ) -> Option<SparkProjection> {
+        let target_date = NaiveDate::parse_from_str(self.target_date.trim(), "%Y-%m-%d").ok()?;
+        let days_left = target_date.signed_duration_since(today).num_days().max(0);
+        let pulls_on = |days: i64| {
+            let (earned_crystals, free_pulls) = self.income(days);
+            (crystals.saturating_add(earned_crystals) / CRYSTALS_PER_PULL)
+                .saturating_add(ticket_pulls.saturating_add(free_pulls))
+        };
+
+        let pulls_on_target_date = pulls_on(days_left);
+        let spark_date = (0..=MAX_PROJECTED_DAYS)
+            .find(|days| pulls_on(*days) >= SPARK_PULLS)
+            .map(|days| today + Duration::days(days));
+        let (earned_crystals, free_pulls) = self.income(days_left);
+        let crystals_needed = SPARK_PULLS
+            .saturating_sub(ticket_pulls.saturating_add(free_pulls))
+            .saturating_mul(CRYSTALS_PER_PULL)
+            .saturating_sub(crystals.saturating_add(earned_crystals));
+
+        Some(SparkProjection {
+            days_left,
+            pulls_on_target_date,
+            spark_date,
+            crystals_needed,
+        })
+    }
+}
+
+/// Today's date as used by the Spark Planner, taken from `get_time()`.
+pub fn today() -> NaiveDate {
+    NaiveDate::parse_from_str(crate::get_time().get(..10).unwrap_or_default(), "%Y-%m-%d")
+        .unwrap_or_else(|_| NaiveDate::from_ymd_opt(1970, 1, 1).unwrap())
+}
+
+pub fn place_spark_planner(
+    plan: &mut SparkPlan,
+    crystals: u32,
+    ticket_pulls: u32,
+    today: NaiveDate,
+    ui: &mut Ui,
+) {
+    ui.heading("Spark Planner");
+    ui.add_space(5.);
+    ui.horizontal(|ui| {
+        ui.label("Target Date: ");
+        ui.add(egui::TextEdit::singleline(&mut plan.target_date).hint_text("YYYY-MM-DD"));
+    });
+    ui.add_space(5.);
+    ui.horizontal(|ui| {
+        ui.label("Crystals per Day: ");
+        ui.add(egui::DragValue::new(&mut plan.daily_crystals).speed(10));
+    });
+    ui.add_space(5.);
+    ui.horizontal(|ui| {
+        ui.label("Bonus Crystals: ");
+        ui.add(egui::DragValue::new(&mut plan.bonus_crystals).speed(100));
+    });
+    ui.add_space(5.);
+    ui.horizontal(|ui| {
+        ui.label("Free Pulls per Day: ");
+        ui.add(egui::DragValue::new(&mut plan.daily_free_pulls).clamp_range(0..=SPARK_PULLS));
+        ui.label("for");
+        ui.add(egui::DragValue::new(&mut plan.free_pull_days));
+        ui.label("days");
+    });
+    ui.add_space(5.);
+
+    if plan.target_date.trim().is_empty() {
+        return;
+    }
+    match plan.project(crystals, ticket_pulls, today) {
+        Some(projection) => {
+            ui.label(format!(
+                "Pulls on {}: {} ({} days away)",
+                plan.target_date.trim(),
+                projection.pulls_on_target_date,
+                projection.days_left
+            ));
+            match projection.spark_date {
+                Some(spark_date) => {
+                    ui.label(format!("Spark on: {}", spark_date.format("%Y-%m-%d")));
+                }
+                None => {
+                    ui.label("Spark on: never at this income");
+                }
+            }
+            if projection.crystals_needed > 0 {
+                ui.label(format!(
+                    "Crystals needed for a spark by {}: {}",
+                    plan.target_date.trim(),
+                    projection.crystals_needed
+                ));
+            } else {
+                ui.label(format!(
+                    "Spark by {} with {} pulls to spare",
+                    plan.target_date.trim(),
+                    projection.pulls_on_target_date.saturating_sub(SPARK_PULLS)
+                ));
+            }
+        }
+        None => {
+            ui.label("Target Date should look like 2022-06-30");
+        }
+    }
+}
+
+/// Chance of an SSR from a single draw, and during Legend Festival / Flash Gala.
+pub const SSR_RATE: f32 = 0.03;
+pub const DOUBLED_SSR_RATE: f32 = 0.06;
+
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum RollType {
+    #[default]
+    Single,
+    TenPull,
+}
+
+impl RollType {
+    pub fn pulls(&self) -> u32 {
+        match *self {
+            RollType::Single => 1,
+            RollType::TenPull => 10,
+        }
+    }
+}
+
+impl fmt::Display for RollType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RollType::Single => write!(f, "Single"),
+            RollType::TenPull => write!(f, "10-Pull"),
+        }
+    }
+}
+
+/// A single draw or 10-pull on a banner, logged the same way an `ItemDrop` is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollDraw {
+    pub roll_id: u32,
+    pub date_obtained: String,
+    pub banner: String,
+    pub roll_type: RollType,
+    /// Whether SSR rates were doubled, i.e. Legend Festival or Flash Gala.
+    #[serde(default)]
+    pub doubled_rates: bool,
+    pub ssrs: u32,
+    /// Notable results such as the SSR characters and summons pulled.
+    #[serde(default)]
+    pub notable: String,
+}
+
+impl RollDraw {
+    pub fn expected_rate(&self) -> f32 {
+        if self.doubled_rates {
+            DOUBLED_SSR_RATE
+        } else {
+            SSR_RATE
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct RollLog {
+    #[serde(default)]
+    pub rolls: Vec<RollDraw>,
+}
+
+impl RollLog {
+    pub fn log(
+        &mut self,
+        banner: &str,
+        roll_type: RollType,
+        doubled_rates: bool,
+        ssrs: u32,
+        notable: &str,
+    ) {
+        let roll_id = self
+            .rolls
+            .iter()
+            .map(|x| x.roll_id + 1)
+            .max()
+            .unwrap_or_default();
+        self.rolls.push(RollDraw {
+            roll_id,
+            date_obtained: crate::get_time(),
+            banner: banner.trim().to_string(),
+            roll_type,
+            doubled_rates,
+            ssrs: ssrs.min(roll_type.pulls()),
+            notable: notable.trim().to_string(),
+        });
+    }
+}
+
+/// Every roll on a banner added up.
+#[derive(PartialEq, Clone, Debug)]
+pub struct BannerProgress {
+    pub banner: String,
+    pub pulls: u32,
+    pub ssrs: u32,
+    /// SSRs the pulls would have given at exactly the advertised rates.
+    pub expected_ssrs: f32,
+}
+
+impl BannerProgress {
+    /// Progress towards a spark, which can go over 1 once the spark is available.
+    pub fn spark_progress(&self) -> f32 {
+        self.pulls as f32 / SPARK_PULLS as f32
+    }
+
+    pub fn ssr_rate(&self) -> f32 {
+        if self.pulls == 0 {
+            return 0.;
+        }
+        self.ssrs as f32 / self.pulls as f32
+    }
+
+    /// The advertised SSR rate averaged over every pull, so a banner that was partly
+    /// pulled during Legfest lands between 3% and 6%.
+    pub fn expected_ssr_rate(&self) -> f32 {
+        if self.pulls == 0 {
+            return 0.;
+        }
+        self.expected_ssrs / self.pulls as f32
+    }
+}
+
+/// Rolls grouped by banner in the order each banner was first pulled on.
+pub fn banner_progress(rolls: &[RollDraw]) -> Vec<BannerProgress> {
+    let mut banners: Vec<BannerProgress> = vec![];
+    for roll in rolls {
+        let pulls = roll.roll_type.pulls();
+        let expected_ssrs = pulls as f32 * roll.expected_rate();
+        match banners.iter_mut().find(|x| x.banner == roll.banner) {
+            Some(banner) => {
+                banner.pulls += pulls;
+                banner.ssrs += roll.ssrs;
+                banner.expected_ssrs += expected_ssrs;
+            }
+            None => banners.push(BannerProgress {
+                banner: roll.banner.clone(),
+                pulls,
+                ssrs: roll.ssrs,
+                expected_ssrs,
+            }),
+        }
+    }
+    banners
+}
+
+/// What's typed into the Roll Log before a roll is logged.
+#[derive(Default)]
+pub struct RollEntry {
+    pub banner: String,
+    pub doubled_rates: bool,
+    pub ssrs: u32,
+    pub notable: String,
+}
+
+pub fn place_roll_log(roll_log: &mut RollLog, entry: &mut RollEntry, ui: &mut Ui) {
+    if entry.banner.is_empty() {
+        if let Some(last_roll) = roll_log.rolls.last() {
+            entry.banner = last_roll.banner.clone();
+            entry.doubled_rates = last_roll.doubled_rates;
+        }
+    }
+
+    ui.heading("Roll Log");
+    ui.add_space(5.);
+    ui.horizontal(|ui| {
+        ui.label("Banner: ");
+        ui.add(egui::TextEdit::singleline(&mut entry.banner).hint_text("e.g. Legfest June 2022"));
+        ui.checkbox(&mut entry.doubled_rates, "6% SSR Rate");
+    });
+    ui.add_space(5.);
+    ui.horizontal(|ui| {
+        ui.label("SSRs: ");
+        ui.add(egui::DragValue::new(&mut entry.ssrs).clamp_range(0..=10));
+        ui.label("Notable: ");
+        ui.add(
+            egui::TextEdit::singleline(&mut entry.notable).hint_text("SSR characters and summons"),
+        );
+    });
+    ui.add_space(5.);
+    ui.horizontal(|ui| {
+        for roll_type in [RollType::Single, RollType::TenPull] {
+            if ui
+                .add_enabled(
+                    !entry.banner.trim().is_empty(),
+                    egui::Button::new(format!("Log {}", roll_type)),
+                )
+                .clicked()
+            {
+                roll_log.log(
+                    &entry.banner,
+                    roll_type,
+                    entry.doubled_rates,
+                    entry.ssrs,
+                    &entry.notable,
+                );
+                entry.ssrs = 0;
+                entry.notable.clear();
+            }
+        }
+    });
+
+    for banner in banner_progress(&roll_log.rolls).iter().rev() {
+        ui.add_space(20.);
+        ui.heading(format!(
+            "{} - {}/{} pulls",
+            banner.banner, banner.pulls, SPARK_PULLS
+        ));
+        ui.add_space(5.);
+        ui.add(egui::ProgressBar::new(banner.spark_progress().min(1.)).show_percentage());
+        ui.label(format!(
+            "SSRs: {} ({} vs {} expected)",
+            banner.ssrs,
+            format_rate(banner.ssr_rate()),
+            format_rate(banner.expected_ssr_rate())
+        ));
+    }
+
+    if !roll_log.rolls.is_empty() {
+        ui.add_space(20.);
+        ui.label("Click a roll to remove it.");
+    }
+    for roll in roll_log.rolls.clone().iter().rev() {
+        let mut roll_text = format!("{} - {} - {} SSR", roll.banner, roll.roll_type, roll.ssrs);
+        if !roll.notable.is_empty() {
+            roll_text = format!("{} ({})", roll_text, roll.notable);
+        }
+        if ui
+            .add(egui::Label::new(roll_text).sense(egui::Sense::click()))
+            .on_hover_text(format!("On {}", roll.date_obtained))
+            .clicked()
+        {
+            roll_log.rolls.retain(|x| x.roll_id != roll.roll_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_amount_allows_separators_and_empty_text() {
+        assert_eq!(parse_amount("1,500"), Some(1500));
+        assert_eq!(parse_amount(" 300 "), Some(300));
+        assert_eq!(parse_amount(""), Some(0));
+        assert_eq!(parse_amount("12a"), None);
+        assert_eq!(parse_amount("-5"), None);
+        assert_eq!(parse_amount("99999999999"), None);
+    }
+
+    #[derive(Deserialize)]
+    struct Saved {
+        #[serde(deserialize_with = "amount_from_number_or_text")]
+        amount: u32,
+    }
+
+    #[test]
+    fn amounts_saved_as_numbers_or_text_both_load() {
+        let load = |json: &str| serde_json::from_str::<Saved>(json).unwrap().amount;
+        assert_eq!(load(r#"{"amount": 9000}"#), 9000);
+        assert_eq!(load(r#"{"amount": "9,000"}"#), 9000);
+        assert_eq!(load(r#"{"amount": ""}"#), 0);
+        assert_eq!(load(r#"{"amount": "lots"}"#), 0);
+    }
+
+    #[test]
+    fn project_counts_crystals_free_pulls_and_tickets() {
+        let plan = SparkPlan {
+            target_date: "2026-01-11".to_string(),
+            daily_crystals: 3000,
+            bonus_crystals: 0,
+            daily_free_pulls: 10,
+            free_pull_days: 5,
+        };
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        // 10 pulls a day from crystals and 10 free pulls a day for 5 days on top of 100 tickets
+        assert_eq!(
+            plan.project(0, 100, today),
+            Some(SparkProjection {
+                days_left: 10,
+                pulls_on_target_date: 250,
+                spark_date: NaiveDate::from_ymd_opt(2026, 1, 16),
+                crystals_needed: 15000,
+            })
+        );
+        let sparked = plan.project(90000, 0, today).unwrap();
+        assert_eq!(sparked.spark_date, Some(today));
+        assert_eq!(sparked.crystals_needed, 0);
+
+        let after_target = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        assert_eq!(plan.project(0, 0, after_target).unwrap().days_left, 0);
+        let no_date = SparkPlan {
+            target_date: "next Legfest".to_string(),
+            ..plan
+        };
+        assert_eq!(no_date.project(0, 0, today), None);
+    }
+}
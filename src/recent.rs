@@ -0,0 +1,156 @@
+use crate::highlight::{self, HighlightRule};
+use crate::textures::item_texture;
+use crate::theme::Theme;
+use crate::{AppSettings, ChestType, CustomImageButton, ItemDrop, Raid};
+use eframe::egui::{self, FontId, RichText, Stroke, TextureId, Ui, Vec2};
+use std::f32::INFINITY;
+
+const LIST_ICON_SIZE: f32 = 18.;
+const GRID_ICON_SIZE: f32 = 32.;
+
+/// When and where `drop` came from.
+fn drop_tooltip(drop: &ItemDrop) -> String {
+    let mut tooltip = format!("On {} from {}", drop.date_obtained, drop.raid);
+    if drop.chest != ChestType::None && drop.chest != ChestType::Blue {
+        tooltip.push_str(&format!(" in a {}", drop.chest));
+    }
+    match &drop.honors {
+        Some(honors) if drop.raid == Raid::PBHL && !honors.is_empty() => {
+            tooltip.push_str(&format!(" {}", honors))
+        }
+        _ => {}
+    }
+    tooltip
+}
+
+/// `text` and `tooltip` in the look of the rule highlighting `drop`, which the tooltip names.
+fn highlighted<'a>(
+    drop: &ItemDrop,
+    text: String,
+    tooltip: String,
+    rules: &'a [HighlightRule],
+    theme: &Theme,
+) -> (RichText, RichText, Option<&'a HighlightRule>) {
+    match highlight::matching_rule(rules, drop) {
+        Some(rule) => (
+            rule.style(&text, theme),
+            rule.style(&format!("{}\n{}", tooltip, rule.name), theme),
+            Some(rule),
+        ),
+        None => (RichText::new(text), RichText::new(tooltip), None),
+    }
+}
+
+/// A drop with its icon and raid. Returns `true` when it's clicked, which removes it.
+fn place_drop_row(
+    drop: &ItemDrop,
+    icon: TextureId,
+    rules: &[HighlightRule],
+    theme: &Theme,
+    ui: &mut Ui,
+) -> bool {
+    let (label, tooltip, _) = highlighted(
+        drop,
+        format!("{} - {}", drop.label(), drop.raid),
+        drop_tooltip(drop),
+        rules,
+        theme,
+    );
+    ui.horizontal(|ui| {
+        ui.spacing_mut().item_spacing.x = 3.;
+        let icon = ui.add(CustomImageButton::new(
+            icon,
+            (LIST_ICON_SIZE, LIST_ICON_SIZE),
+        ));
+        let label = ui.add(egui::Label::new(label).sense(egui::Sense::click()));
+        (icon | label).on_hover_text(tooltip).clicked()
+    })
+    .inner
+}
+
+/// The icon of a run of drops with how many dropped in its corner, outlined in the color of the
+/// rule highlighting it. Returns `true` when it's clicked, which removes the newest drop of the run.
+fn place_drop_group(
+    newest: &ItemDrop,
+    count: u32,
+    icon: TextureId,
+    rules: &[HighlightRule],
+    theme: &Theme,
+    ui: &mut Ui,
+) -> bool {
+    let (_, tooltip, rule) = highlighted(
+        newest,
+        String::new(),
+        format!("{} x{}\n{}", newest.item, count, drop_tooltip(newest)),
+        rules,
+        theme,
+    );
+    let response = ui.add(CustomImageButton::new(
+        icon,
+        (GRID_ICON_SIZE, GRID_ICON_SIZE),
+    ));
+    if let Some(rule) = rule {
+        ui.painter().rect_stroke(
+            response.rect.expand(1.),
+            3.,
+            Stroke::new(2., rule.color.color(theme)),
+        );
+    }
+    if count > 1 {
+        let visuals = ui.visuals();
+        let galley = ui.painter().layout_no_wrap(
+            format!("x{}", count),
+            FontId::proportional(11.),
+            visuals.strong_text_color(),
+        );
+        let size = galley.size() + Vec2::new(4., 0.);
+        let badge = egui::Rect::from_min_size(response.rect.right_bottom() - size, size);
+        ui.painter()
+            .rect_filled(badge, 3., visuals.extreme_bg_color);
+        ui.painter()
+            .galley(badge.center() - galley.size() / 2., galley);
+    }
+    response.on_hover_text(tooltip).clicked()
+}
+
+/// Recent Drops, newest first, as a list or a grid grouping runs of the same drop. Clicking a
+/// drop removes it.
+pub fn place_recent_drops(settings: &mut AppSettings, theme: &Theme, ui: &mut Ui) {
+    let drops = &settings.droplog.drop;
+    let shown = settings.stats.shown();
+    let rules = &settings.app_settings.highlight_rules;
+    let mut removed = None;
+    let scroll_area = egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .max_height(INFINITY)
+        .max_width(INFINITY);
+    if settings.app_settings.recent_drops_grid {
+        scroll_area.show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for run in settings.stats.runs().iter().rev() {
+                    let newest = &drops[run.newest];
+                    let icon = item_texture(ui.ctx(), newest.item, newest.raid, newest.chest);
+                    if place_drop_group(newest, run.quantity, icon, rules, theme, ui) {
+                        removed = Some(newest.drop_id);
+                    }
+                }
+            });
+        });
+    } else {
+        let row_height = ui
+            .text_style_height(&egui::TextStyle::Body)
+            .max(LIST_ICON_SIZE);
+        scroll_area.show_rows(ui, row_height, shown.len(), |ui, range| {
+            for row in range {
+                let drop = &drops[shown[shown.len() - 1 - row]];
+                let icon = item_texture(ui.ctx(), drop.item, drop.raid, drop.chest);
+                if place_drop_row(drop, icon, rules, theme, ui) {
+                    removed = Some(drop.drop_id);
+                }
+            }
+        });
+    }
+    if let Some(drop_id) = removed {
+        settings.remove_drop(drop_id);
+    }
+}
@@ -0,0 +1,674 @@
+use crate::{
+    drop_rate, dry_streak, format_rate, item_count, item_image, total_kills, AppSettings,
+    ChestType, DropLog, Item, ItemDrop, Raid,
+};
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use eframe::egui::{self, ColorImage, FontDefinitions, TextureHandle, Ui};
+use image::{imageops, DynamicImage, ImageOutputFormat, Rgba, RgbaImage};
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Cursor;
+
+const CARD_WIDTH: u32 = 420;
+const PADDING: u32 = 16;
+const LINE_HEIGHT: u32 = 26;
+const ICON_SIZE: u32 = 32;
+const ICON_COLUMNS: u32 = 4;
+const ICON_COLUMN_WIDTH: u32 = (CARD_WIDTH - PADDING * 2) / ICON_COLUMNS;
+
+/// Start of the URL fragment a share link carries its totals in.
+pub const SHARED_TOTALS_PREFIX: &str = "#totals=";
+const SHARED_TOTALS_VERSION: &str = "3";
+/// Drops a share link may expand to, so a mangled link can't make Dorothy allocate forever.
+const MAX_SHARED_DROPS: usize = 100_000;
+
+const BACKGROUND: Rgba<u8> = Rgba([27, 27, 27, 255]);
+const TEXT: Rgba<u8> = Rgba([220, 220, 220, 255]);
+const GOLD_BRICK_TEXT: Rgba<u8> = Rgba([255, 221, 26, 255]);
+const FADED_TEXT: Rgba<u8> = Rgba([140, 140, 140, 255]);
+
+/// State of the "Share Stats" window.
+#[derive(Default)]
+pub struct ShareStats {
+    pub open: bool,
+    pub raid: Raid,
+    pub status: Option<String>,
+    /// The rendered card along with the raid and drop count it was rendered for.
+    preview: Option<(Raid, usize, RgbaImage, TextureHandle)>,
+}
+
+/// Every item other than "No Drop" that dropped from a raid, in the order they were first logged.
+fn dropped_items(drops: &[ItemDrop], raid: Raid) -> Vec<(Item, ChestType)> {
+    let mut items: Vec<(Item, ChestType)> = vec![];
+    for drop in drops
+        .iter()
+        .filter(|x| x.raid == raid && x.item != Item::NoDrop)
+    {
+        if !items.contains(&(drop.item, drop.chest)) {
+            items.push((drop.item, drop.chest));
+        }
+    }
+    items
+}
+
+/// Draws `text` with its top left corner at `x`, `y` and returns how wide it was.
+fn draw_text(
+    card: &mut RgbaImage,
+    font: &FontRef<'_>,
+    text: &str,
+    (x, y): (u32, u32),
+    size: f32,
+    color: Rgba<u8>,
+) -> f32 {
+    let scaled_font = font.as_scaled(PxScale::from(size));
+    let mut caret = x as f32;
+    let mut previous = None;
+    for c in text.chars() {
+        let glyph_id = scaled_font.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += scaled_font.kern(previous, glyph_id);
+        }
+        let glyph = glyph_id.with_scale_and_position(
+            size,
+            ab_glyph::point(caret, y as f32 + scaled_font.ascent()),
+        );
+        caret += scaled_font.h_advance(glyph_id);
+        previous = Some(glyph_id);
+
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|glyph_x, glyph_y, coverage| {
+                let pixel_x = bounds.min.x as i32 + glyph_x as i32;
+                let pixel_y = bounds.min.y as i32 + glyph_y as i32;
+                if pixel_x < 0
+                    || pixel_y < 0
+                    || pixel_x as u32 >= card.width()
+                    || pixel_y as u32 >= card.height()
+                {
+                    return;
+                }
+                let pixel = card.get_pixel_mut(pixel_x as u32, pixel_y as u32);
+                for channel in 0..3 {
+                    pixel[channel] = (pixel[channel] as f32 * (1. - coverage)
+                        + color[channel] as f32 * coverage)
+                        as u8;
+                }
+            });
+        }
+    }
+    caret - x as f32
+}
+
+/// Renders a summary card of a raid's drops: kills, gold bars, dry streak and every item's count.
+pub fn render_stats_card(drops: &[ItemDrop], raid: Raid) -> RgbaImage {
+    let items = dropped_items(drops, raid);
+    let icon_rows = items.chunks(ICON_COLUMNS as usize).count() as u32;
+    let card_height =
+        PADDING * 2 + 40 + LINE_HEIGHT * 3 + 10 + icon_rows * (ICON_SIZE + 8) + LINE_HEIGHT;
+    let mut card = RgbaImage::from_pixel(CARD_WIDTH, card_height, BACKGROUND);
+
+    let font_definitions = FontDefinitions::default();
+    let font_data = match font_definitions.font_data.get("Ubuntu-Light") {
+        Some(font_data) => font_data,
+        None => return card,
+    };
+    let font = match FontRef::try_from_slice_and_index(&font_data.font, font_data.index) {
+        Ok(font) => font,
+        Err(_) => return card,
+    };
+
+    let kills = total_kills(drops, raid);
+    let gold_bricks: usize = ChestType::ALL
+        .iter()
+        .map(|&chest| item_count(drops, raid, Item::GoldBrick, chest))
+        .sum();
+    // Rated per chest like Drop Totals, since each chest is rolled in different kills
+    let chest_rates: Vec<String> = ChestType::ALL
+        .iter()
+        .filter_map(|&chest| {
+            drop_rate(drops, raid, Item::GoldBrick, chest, true)
+                .map(|rate| format!("{} {}", chest, format_rate(rate)))
+        })
+        .collect();
+    let gold_brick_rate = if chest_rates.is_empty() {
+        "".to_string()
+    } else {
+        format!(" ({} of kills)", chest_rates.join(", "))
+    };
+
+    let mut y = PADDING;
+    draw_text(
+        &mut card,
+        &font,
+        &format!("Dorothy - {}", raid),
+        (PADDING, y),
+        28.,
+        TEXT,
+    );
+    y += 40;
+    draw_text(
+        &mut card,
+        &font,
+        &format!("Kills: {}", kills),
+        (PADDING, y),
+        20.,
+        TEXT,
+    );
+    y += LINE_HEIGHT;
+    draw_text(
+        &mut card,
+        &font,
+        &format!("Gold Bricks: {}{}", gold_bricks, gold_brick_rate),
+        (PADDING, y),
+        20.,
+        GOLD_BRICK_TEXT,
+    );
+    y += LINE_HEIGHT;
+    draw_text(
+        &mut card,
+        &font,
+        &format!(
+            "Dry Streak: {} kills",
+            dry_streak(drops, raid, Item::GoldBrick)
+        ),
+        (PADDING, y),
+        20.,
+        TEXT,
+    );
+    y += LINE_HEIGHT + 10;
+
+    for (pos, (item, chest)) in items.iter().enumerate() {
+        let x = PADDING + (pos as u32 % ICON_COLUMNS) * ICON_COLUMN_WIDTH;
+        let icon_y = y + (pos as u32 / ICON_COLUMNS) * (ICON_SIZE + 8);
+        if let Ok(icon) = image::load_from_memory(item_image(*item, raid, *chest)) {
+            let icon = imageops::resize(
+                &icon.to_rgba8(),
+                ICON_SIZE,
+                ICON_SIZE,
+                imageops::FilterType::Triangle,
+            );
+            imageops::overlay(&mut card, &icon, x as i64, icon_y as i64);
+        }
+        let color = if *item == Item::GoldBrick {
+            GOLD_BRICK_TEXT
+        } else {
+            TEXT
+        };
+        draw_text(
+            &mut card,
+            &font,
+            &format!("x{}", item_count(drops, raid, *item, *chest)),
+            (x + ICON_SIZE + 4, icon_y + 6),
+            18.,
+            color,
+        );
+    }
+
+    draw_text(
+        &mut card,
+        &font,
+        "Logged with Dorothy",
+        (PADDING, card_height - PADDING - 16),
+        14.,
+        FADED_TEXT,
+    );
+    card
+}
+
+pub fn card_to_png(card: &RgbaImage) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut png = vec![];
+    DynamicImage::ImageRgba8(card.clone())
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)?;
+    Ok(png)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn copy_card_to_clipboard(card: &RgbaImage) -> Result<(), Box<dyn Error>> {
+    let mut clipboard = arboard::Clipboard::new()?;
+    clipboard.set_image(arboard::ImageData {
+        width: card.width() as usize,
+        height: card.height() as usize,
+        bytes: std::borrow::Cow::Borrowed(card.as_raw()),
+    })?;
+    Ok(())
+}
+
+pub fn place_share_stats(state: &mut ShareStats, drops: &[ItemDrop], ui: &mut Ui) {
+    egui::ComboBox::from_label("Raid")
+        .selected_text(state.raid.to_string())
+        .show_ui(ui, |ui| {
+            for raid in Raid::ALL {
+                if drops.iter().any(|x| x.raid == raid) {
+                    ui.selectable_value(&mut state.raid, raid, raid.to_string());
+                }
+            }
+        });
+
+    let is_outdated = match &state.preview {
+        Some((raid, drop_count, _, _)) => *raid != state.raid || *drop_count != drops.len(),
+        None => true,
+    };
+    if is_outdated {
+        let card = render_stats_card(drops, state.raid);
+        let texture = ui.ctx().load_texture(
+            "share_stats_card",
+            ColorImage::from_rgba_unmultiplied(
+                [card.width() as usize, card.height() as usize],
+                card.as_raw(),
+            ),
+        );
+        state.preview = Some((state.raid, drops.len(), card, texture));
+    }
+
+    if let Some((raid, _, card, texture)) = &state.preview {
+        ui.image(texture, texture.size_vec2());
+        ui.horizontal(|ui| {
+            if ui.button("Save PNG").clicked() {
+                let file_name = crate::export::export_file_name(
+                    total_kills(drops, *raid),
+                    &format!("{}-kills", raid).to_lowercase().replace(' ', "-"),
+                    "png",
+                );
+                state.status = Some(
+                    match card_to_png(card)
+                        .and_then(|png| crate::export::save_export(&file_name, "image/png", &png))
+                    {
+                        Ok(()) => format!("Saved {}", file_name),
+                        Err(e) => format!("Dorothy couldn't save the card: {}", e),
+                    },
+                );
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui.button("Copy to Clipboard").clicked() {
+                state.status = Some(match copy_card_to_clipboard(card) {
+                    Ok(()) => "Copied to clipboard".to_string(),
+                    Err(e) => format!("Dorothy couldn't copy the card: {}", e),
+                });
+            }
+        });
+    }
+    if let Some(status) = &state.status {
+        ui.label(status);
+    }
+}
+
+/// What every kill dropped, with kills that dropped the same items counted together. This is all
+/// Drop Totals needs to recalculate its kills and rates, so it's what a share link carries
+/// instead of the log.
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct SharedTotals {
+    pub kills: Vec<SharedKills>,
+}
+
+/// Kills that each dropped the same items from the same raids and chests.
+#[derive(PartialEq, Clone, Debug)]
+pub struct SharedKills {
+    pub kills: usize,
+    pub counts: Vec<SharedCount>,
+}
+
+/// The drops of one item from one raid and chest in each of the kills. Drops count towards kills
+/// and rates, while `quantity` adds up the quantity of the item over all of the kills.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct SharedCount {
+    pub raid: Raid,
+    pub item: Item,
+    pub chest: ChestType,
+    pub drops: usize,
+    pub quantity: usize,
+}
+
+impl SharedCount {
+    fn positions(&self) -> Option<(usize, usize, usize)> {
+        Some((
+            Raid::ALL.iter().position(|x| *x == self.raid)?,
+            Item::ALL.iter().position(|x| *x == self.item)?,
+            ChestType::ALL.iter().position(|x| *x == self.chest)?,
+        ))
+    }
+}
+
+fn to_base36(mut value: usize) -> String {
+    let mut digits = vec![];
+    loop {
+        digits.push(std::char::from_digit((value % 36) as u32, 36).unwrap_or('0'));
+        value /= 36;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+fn from_base36(digits: &str) -> Option<usize> {
+    usize::from_str_radix(digits, 36).ok()
+}
+
+impl SharedTotals {
+    pub fn from_drops(drops: &[ItemDrop]) -> Self {
+        // Drops without a kill id were logged before kills were and are a kill each.
+        let mut kills: Vec<Vec<&ItemDrop>> = vec![];
+        let mut kill_positions = HashMap::new();
+        for drop in drops {
+            match drop
+                .kill_id
+                .map(|id| *kill_positions.entry(id).or_insert(kills.len()))
+            {
+                Some(pos) if pos < kills.len() => kills[pos].push(drop),
+                _ => kills.push(vec![drop]),
+            }
+        }
+
+        let mut totals = Self::default();
+        let mut kill_groups = HashMap::new();
+        for kill in kills {
+            let mut counts: Vec<SharedCount> = vec![];
+            for drop in kill {
+                match counts
+                    .iter_mut()
+                    .find(|x| x.raid == drop.raid && x.item == drop.item && x.chest == drop.chest)
+                {
+                    Some(count) => {
+                        count.drops += 1;
+                        count.quantity += drop.quantity as usize;
+                    }
+                    None => counts.push(SharedCount {
+                        raid: drop.raid,
+                        item: drop.item,
+                        chest: drop.chest,
+                        drops: 1,
+                        quantity: drop.quantity as usize,
+                    }),
+                }
+            }
+            counts.sort_by_key(SharedCount::positions);
+            let signature: Vec<_> = counts.iter().map(|x| (x.positions(), x.drops)).collect();
+            match kill_groups.get(&signature) {
+                Some(&pos) => {
+                    let group: &mut SharedKills = &mut totals.kills[pos];
+                    group.kills += 1;
+                    for (total, count) in group.counts.iter_mut().zip(counts) {
+                        total.quantity += count.quantity;
+                    }
+                }
+                None => {
+                    kill_groups.insert(signature, totals.kills.len());
+                    totals.kills.push(SharedKills { kills: 1, counts });
+                }
+            }
+        }
+        totals.kills.sort_by(|a, b| {
+            let positions = |x: &SharedKills| -> Vec<_> {
+                x.counts.iter().map(|x| (x.positions(), x.drops)).collect()
+            };
+            positions(a).cmp(&positions(b))
+        });
+        totals
+    }
+
+    /// Drops the totals expand to.
+    fn drop_count(&self) -> Option<usize> {
+        self.kills.iter().try_fold(0usize, |total, group| {
+            let drops: usize = group.counts.iter().map(|x| x.drops).sum();
+            total.checked_add(group.kills.checked_mul(drops)?)
+        })
+    }
+
+    /// Encodes the totals as `3.<kills>.<kills>...` where every group of kills is the number of
+    /// kills in base 36, `~` and its entries separated by `-`. An entry is the raid, item and
+    /// chest as a single base 36 digit (their position in `ALL`), then the drops per kill in base
+    /// 36 unless it's one, then `_` and the quantity over all the kills in base 36 unless it's one
+    /// per drop.
+    /// The same totals always encode to the same text. Totals with more drops than a link may
+    /// open are refused, since nobody could open the link.
+    pub fn encode(&self) -> Result<String, String> {
+        let total = self.drop_count().unwrap_or(usize::MAX);
+        if total > MAX_SHARED_DROPS {
+            return Err(format!(
+                "Share links can hold up to {} drops and your log has {}.",
+                MAX_SHARED_DROPS, total
+            ));
+        }
+        let mut encoded = SHARED_TOTALS_VERSION.to_string();
+        for group in &self.kills {
+            let mut entries = vec![];
+            for count in &group.counts {
+                let (raid, item, chest) = match count.positions() {
+                    Some(positions) => positions,
+                    None => continue,
+                };
+                let mut entry = [raid, item, chest].map(to_base36).concat();
+                if count.drops != 1 {
+                    entry.push_str(&to_base36(count.drops));
+                }
+                if count.quantity != count.drops * group.kills {
+                    entry.push('_');
+                    entry.push_str(&to_base36(count.quantity));
+                }
+                entries.push(entry);
+            }
+            encoded.push('.');
+            encoded.push_str(&to_base36(group.kills));
+            encoded.push('~');
+            encoded.push_str(&entries.join("-"));
+        }
+        Ok(encoded)
+    }
+
+    /// Reads totals written by `encode`, with or without the `#totals=` prefix.
+    /// Returns `None` for anything that isn't a valid link rather than showing partial totals.
+    pub fn decode(text: &str) -> Option<Self> {
+        let text = text.strip_prefix(SHARED_TOTALS_PREFIX).unwrap_or(text);
+        let mut groups = text.split('.');
+        if groups.next()? != SHARED_TOTALS_VERSION {
+            return None;
+        }
+        let mut totals = Self::default();
+        for group in groups {
+            let (kills, entries) = group.split_once('~')?;
+            let kills = from_base36(kills)?;
+            let mut counts = vec![];
+            for entry in entries.split('-') {
+                let mut digits = entry.chars();
+                let mut next_index = || digits.next()?.to_digit(36).map(|x| x as usize);
+                let raid = *Raid::ALL.get(next_index()?)?;
+                let item = *Item::ALL.get(next_index()?)?;
+                let chest = *ChestType::ALL.get(next_index()?)?;
+                let (drops, quantity) = match digits.as_str().split_once('_') {
+                    Some((drops, quantity)) => (drops, Some(from_base36(quantity)?)),
+                    None => (digits.as_str(), None),
+                };
+                let drops = match drops {
+                    "" => 1,
+                    drops => from_base36(drops)?,
+                };
+                let all_drops = drops.checked_mul(kills)?;
+                let quantity = quantity.unwrap_or(all_drops);
+                // Every drop is at least one of the item and no more than a drop's quantity can
+                // hold.
+                if all_drops == 0
+                    || quantity < all_drops
+                    || quantity / all_drops >= u32::MAX as usize
+                {
+                    return None;
+                }
+                counts.push(SharedCount {
+                    raid,
+                    item,
+                    chest,
+                    drops,
+                    quantity,
+                });
+            }
+            totals.kills.push(SharedKills { kills, counts });
+            if totals.drop_count()? > MAX_SHARED_DROPS {
+                return None;
+            }
+        }
+        Some(totals)
+    }
+
+    /// Undated drops with the same counts, for passing to the functions Drop Totals is built on.
+    /// Drops of a kill share a made up kill id and quantities are spread over the drops as
+    /// evenly as they go.
+    pub fn to_drops(&self) -> Vec<ItemDrop> {
+        let mut drops = vec![];
+        let mut kill_id = 0;
+        for group in &self.kills {
+            let drops_per_kill: usize = group.counts.iter().map(|x| x.drops).sum();
+            for kill in 0..group.kills {
+                kill_id += 1;
+                for count in &group.counts {
+                    let all_drops = count.drops * group.kills;
+                    for pos in kill * count.drops..(kill + 1) * count.drops {
+                        let mut drop = ItemDrop::new(
+                            drops.len() as u32,
+                            String::new(),
+                            count.raid,
+                            count.item,
+                            count.chest,
+                            Some(String::new()),
+                        );
+                        let extra = (pos < count.quantity % all_drops) as usize;
+                        drop.quantity = (count.quantity / all_drops + extra) as u32;
+                        if drops_per_kill > 1 {
+                            drop.kill_id = Some(kill_id);
+                        }
+                        drops.push(drop);
+                    }
+                }
+            }
+        }
+        drops
+    }
+
+    /// Settings to show these totals with. Every raid is shown since the link isn't tied to a tab.
+    pub fn to_settings(&self, settings: &AppSettings) -> AppSettings {
+        let mut shared_settings = AppSettings {
+            version: settings.version,
+            app_settings: settings.app_settings.clone(),
+            droplog: DropLog {
+                drop: self.to_drops(),
+            },
+            ..Default::default()
+        };
+        shared_settings.app_settings.show_all_drops = true;
+        shared_settings.update_stats();
+        shared_settings
+    }
+}
+
+/// Link to the webapp that opens these totals in a read-only Drop Totals view, or why there
+/// can't be one.
+#[cfg(target_arch = "wasm32")]
+pub fn share_link(totals: &SharedTotals) -> Result<String, String> {
+    let encoded = totals.encode()?;
+    let location = web_sys::window()
+        .map(|window| window.location())
+        .ok_or("Dorothy couldn't find the webapp's address.")?;
+    match (location.origin(), location.pathname()) {
+        (Ok(origin), Ok(pathname)) => Ok(format!(
+            "{}{}{}{}",
+            origin, pathname, SHARED_TOTALS_PREFIX, encoded
+        )),
+        _ => Err("Dorothy couldn't find the webapp's address.".to_string()),
+    }
+}
+
+/// Totals from the link the webapp was opened with, if it was opened from a share link.
+#[cfg(target_arch = "wasm32")]
+pub fn shared_totals_from_location() -> Option<SharedTotals> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    if !hash.starts_with(SHARED_TOTALS_PREFIX) {
+        return None;
+    }
+    SharedTotals::decode(&hash)
+}
+
+/// Drops the totals from the address bar so reloading opens the user's own log again.
+#[cfg(target_arch = "wasm32")]
+pub fn clear_shared_totals_location() {
+    if let Some(window) = web_sys::window() {
+        let _ = window.location().set_hash("");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::{chest_kills, drop_count, hosts_kills, raid_kills};
+
+    fn shared(drops: &[ItemDrop]) -> Vec<ItemDrop> {
+        let totals = SharedTotals::from_drops(drops);
+        let encoded = totals.encode().unwrap();
+        assert_eq!(SharedTotals::decode(&encoded), Some(totals.clone()));
+        totals.to_drops()
+    }
+
+    #[test]
+    fn quantities_are_shared_apart_from_drops() {
+        let drops = vec![
+            ItemDrop::of(Raid::Akasha, Item::NoDrop, ChestType::None, None),
+            ItemDrop::of(Raid::Akasha, Item::GoldBrick, ChestType::Blue, None),
+            ItemDrop::of(Raid::Wilnas, Item::EternitySand, ChestType::Gold, None).with_quantity(3),
+            ItemDrop::of(Raid::Wilnas, Item::EternitySand, ChestType::Gold, None).with_quantity(2),
+        ];
+        let shared = shared(&drops);
+        assert_eq!(shared.len(), drops.len());
+        for raid in [Raid::Akasha, Raid::Wilnas] {
+            assert_eq!(total_kills(&shared, raid), total_kills(&drops, raid));
+        }
+        assert_eq!(
+            item_count(&shared, Raid::Wilnas, Item::EternitySand, ChestType::Gold),
+            5
+        );
+    }
+
+    #[test]
+    fn kills_are_shared_with_their_drops_together() {
+        let drops = vec![
+            ItemDrop::of(Raid::UBHL, Item::GoldBrick, ChestType::Host, Some(1)),
+            ItemDrop::of(Raid::UBHL, Item::ChampionMerit, ChestType::Blue, Some(1)),
+            ItemDrop::of(Raid::UBHL, Item::ChampionMerit, ChestType::Blue, Some(2)),
+            ItemDrop::of(Raid::UBHL, Item::ChampionMerit, ChestType::Blue, Some(3)),
+            ItemDrop::of(Raid::UBHL, Item::GoldBrick, ChestType::Flip, Some(3)),
+            ItemDrop::of(Raid::Akasha, Item::SupremeMerit, ChestType::Blue, Some(4)),
+            ItemDrop::of(Raid::Akasha, Item::EternitySand, ChestType::Gold, Some(4))
+                .with_quantity(3),
+            ItemDrop::of(Raid::Akasha, Item::SupremeMerit, ChestType::Blue, Some(5)),
+            ItemDrop::of(Raid::Akasha, Item::EternitySand, ChestType::Gold, Some(5)),
+            ItemDrop::of(Raid::Akasha, Item::NoDrop, ChestType::None, None),
+            ItemDrop::of(Raid::Akasha, Item::NoDrop, ChestType::None, None),
+        ];
+        let shared = shared(&drops);
+        assert_eq!(hosts_kills(&shared), hosts_kills(&drops));
+        for raid in [Raid::UBHL, Raid::Akasha] {
+            assert_eq!(raid_kills(&shared, raid), raid_kills(&drops, raid));
+            for chest in ChestType::ALL {
+                assert_eq!(
+                    chest_kills(&shared, raid, chest),
+                    chest_kills(&drops, raid, chest)
+                );
+                for item in Item::ALL {
+                    assert_eq!(
+                        drop_count(&shared, raid, item, chest),
+                        drop_count(&drops, raid, item, chest)
+                    );
+                    assert_eq!(
+                        item_count(&shared, raid, item, chest),
+                        item_count(&drops, raid, item, chest)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mangled_links_are_refused() {
+        assert_eq!(SharedTotals::decode("2.0310"), None);
+        assert_eq!(SharedTotals::decode("3.0310"), None);
+        assert_eq!(SharedTotals::decode("3.0~031"), None);
+        assert_eq!(SharedTotals::decode("3.2~0311_1"), None);
+        assert_eq!(SharedTotals::decode("3.zzzz~031"), None);
+    }
+}
@@ -0,0 +1,731 @@
+use crate::{ChestType, Item, ItemDrop, Raid};
+use std::collections::{HashMap, HashSet};
+
+/// Raids whose host and flip chests are logged on the Hosts tab. PBHL's host chest is too, but
+/// the rest of PBHL is logged on its own tab.
+pub const HOSTS_RAIDS: [Raid; 5] = [
+    Raid::UBHL,
+    Raid::Xeno,
+    Raid::Huanglong,
+    Raid::Qilin,
+    Raid::HLQL,
+];
+
+/// Kills the drops came from. Drops logged together as one kill count once and drops logged on
+/// their own, including every drop from before kills could be logged, count as a kill each.
+pub fn kill_count<'a>(drops: impl IntoIterator<Item = &'a ItemDrop>) -> usize {
+    let mut kill_ids = HashSet::new();
+    drops
+        .into_iter()
+        .filter(|x| match x.kill_id {
+            Some(kill_id) => kill_ids.insert(kill_id),
+            None => true,
+        })
+        .count()
+}
+
+fn is_host_or_flip(chest: ChestType) -> bool {
+    chest == ChestType::Host || chest == ChestType::Flip
+}
+
+/// Whether a drop was logged on the Hosts tab and counts towards its total.
+pub fn is_hosts_drop(drop: &ItemDrop) -> bool {
+    HOSTS_RAIDS.contains(&drop.raid) || (drop.raid == Raid::PBHL && drop.chest == ChestType::Host)
+}
+
+/// Kills of a raid. Every kill rolls the blue chest, which is logged as the item it dropped or as
+/// No Drop, and the gold and MVP chests are logged along with it. Host and flip chests are left
+/// out since the Hosts tab logs them apart from the rest of the kill, see `hosts_kills`.
+pub fn raid_kills(drops: &[ItemDrop], raid: Raid) -> usize {
+    kill_count(
+        drops
+            .iter()
+            .filter(|x| x.raid == raid && !is_host_or_flip(x.chest)),
+    )
+}
+
+/// Kills of a raid that something was logged from `chest` for. No Drop counts as the blue chest
+/// being empty rather than as a chest of its own, so it isn't counted for any chest.
+pub fn chest_kills(drops: &[ItemDrop], raid: Raid, chest: ChestType) -> usize {
+    kill_count(
+        drops
+            .iter()
+            .filter(|x| x.raid == raid && x.chest == chest && x.item != Item::NoDrop),
+    )
+}
+
+/// Kills of a raid without a blue chest.
+pub fn no_drop_count(drops: &[ItemDrop], raid: Raid) -> usize {
+    kill_count(
+        drops
+            .iter()
+            .filter(|x| x.item == Item::NoDrop && x.raid == raid),
+    )
+}
+
+/// Kills logged on the Hosts tab. Only the gold bars host and flip chests drop are logged there,
+/// so this is every host and flip chest that dropped one.
+pub fn hosts_kills(drops: &[ItemDrop]) -> usize {
+    kill_count(drops.iter().filter(|x| is_hosts_drop(x)))
+}
+
+/// Kills shown in a raid's Drop Totals heading. UBHL and Xeno share a single "Hosts" total.
+pub fn total_kills(drops: &[ItemDrop], raid: Raid) -> usize {
+    if raid == Raid::UBHL || raid == Raid::Xeno {
+        return hosts_kills(drops);
+    }
+    raid_kills(drops, raid)
+}
+
+/// Every kill count of a raid at once.
+#[derive(PartialEq, Copy, Clone, Default, Debug)]
+pub struct RaidKills {
+    /// See `raid_kills`.
+    pub kills: usize,
+    pub no_drops: usize,
+    /// Kills per chest, see `chest_kills`. A kill with drops from several chests counts towards
+    /// each of them.
+    pub blue: usize,
+    pub host: usize,
+    pub flip: usize,
+    pub mvp: usize,
+    pub gold: usize,
+}
+
+impl RaidKills {
+    pub fn of(drops: &[ItemDrop], raid: Raid) -> Self {
+        Self {
+            kills: raid_kills(drops, raid),
+            no_drops: no_drop_count(drops, raid),
+            blue: chest_kills(drops, raid, ChestType::Blue),
+            host: chest_kills(drops, raid, ChestType::Host),
+            flip: chest_kills(drops, raid, ChestType::Flip),
+            mvp: chest_kills(drops, raid, ChestType::Mvp),
+            gold: chest_kills(drops, raid, ChestType::Gold),
+        }
+    }
+
+    /// One line per chest anything was logged from, e.g. "Blue Chest: 12 kills".
+    pub fn breakdown(&self) -> String {
+        [
+            ("No Drop", self.no_drops),
+            ("Blue Chest", self.blue),
+            ("Host Chest", self.host),
+            ("Flip Chest", self.flip),
+            ("MVP Chest", self.mvp),
+            ("Gold Chest", self.gold),
+        ]
+        .iter()
+        .filter(|(_, kills)| *kills > 0)
+        .map(|(chest, kills)| format!("{}: {} kills", chest, kills))
+        .collect::<Vec<String>>()
+        .join("\n")
+    }
+}
+
+/// Total amount of an item, counting every piece of drops that came in a quantity.
+pub fn item_count(drops: &[ItemDrop], raid: Raid, item: Item, chest: ChestType) -> usize {
+    drops
+        .iter()
+        .filter(|x| x.item == item && x.raid == raid && x.chest == chest)
+        .map(|x| x.quantity as usize)
+        .sum()
+}
+
+/// Kills an item dropped from, however many of it each one dropped.
+pub fn drop_count(drops: &[ItemDrop], raid: Raid, item: Item, chest: ChestType) -> usize {
+    drops
+        .iter()
+        .filter(|x| x.item == item && x.raid == raid && x.chest == chest)
+        .count()
+}
+
+/// Kills since `item` last dropped from `raid`, or every kill if it never has.
+pub fn dry_streak(drops: &[ItemDrop], raid: Raid, item: Item) -> usize {
+    let raid_drops = || drops.iter().rev().filter(|x| x.raid == raid);
+    // Other drops from the kill `item` last dropped in may have been logged after it.
+    let last_kill_id = raid_drops()
+        .find(|x| x.item == item)
+        .and_then(|x| x.kill_id);
+    kill_count(
+        raid_drops()
+            .take_while(|x| x.item != item)
+            .filter(|x| x.kill_id.is_none() || x.kill_id != last_kill_id)
+            .filter(|x| !is_host_or_flip(x.chest)),
+    )
+}
+
+/// Drop rate shown next to an item in Drop Totals, or `None` when no percentage is shown.
+///
+/// For an item that dropped from `n` kills:
+///
+/// * blue chest: `n / (kills - no drops)`, how often a blue chest has it, or `n / kills` when
+///   calculating drop rates by total kills
+/// * No Drop: `no drops / kills`, only shown when calculating drop rates by total kills
+/// * gold and MVP chests: `n / kills`, since every kill rolls them
+/// * host and flip chests: `n / Hosts kills`, the same total the Hosts heading shows
+pub fn drop_rate(
+    drops: &[ItemDrop],
+    raid: Raid,
+    item: Item,
+    chest: ChestType,
+    droprate_by_kills: bool,
+) -> Option<f32> {
+    rate(drops, raid, item, chest, droprate_by_kills)
+}
+
+/// The counts a drop rate is made of, from the log itself or from a `DropIndex`.
+trait DropCounts {
+    fn drop_count(&self, raid: Raid, item: Item, chest: ChestType) -> usize;
+    fn raid_kills(&self, raid: Raid) -> usize;
+    fn no_drop_count(&self, raid: Raid) -> usize;
+    fn hosts_kills(&self) -> usize;
+}
+
+impl DropCounts for [ItemDrop] {
+    fn drop_count(&self, raid: Raid, item: Item, chest: ChestType) -> usize {
+        drop_count(self, raid, item, chest)
+    }
+
+    fn raid_kills(&self, raid: Raid) -> usize {
+        raid_kills(self, raid)
+    }
+
+    fn no_drop_count(&self, raid: Raid) -> usize {
+        no_drop_count(self, raid)
+    }
+
+    fn hosts_kills(&self) -> usize {
+        hosts_kills(self)
+    }
+}
+
+fn rate<T: DropCounts + ?Sized>(
+    counts: &T,
+    raid: Raid,
+    item: Item,
+    chest: ChestType,
+    droprate_by_kills: bool,
+) -> Option<f32> {
+    let items_dropped = counts.drop_count(raid, item, chest);
+    if items_dropped == 0 {
+        return None;
+    }
+    let kills = match chest {
+        ChestType::Host | ChestType::Flip => counts.hosts_kills(),
+        ChestType::None if !droprate_by_kills => return None,
+        ChestType::Blue if !droprate_by_kills => counts
+            .raid_kills(raid)
+            .saturating_sub(counts.no_drop_count(raid)),
+        _ => counts.raid_kills(raid),
+    };
+    if kills == 0 {
+        return None;
+    }
+    Some(items_dropped as f32 / kills as f32)
+}
+
+/// Kills counted one drop at a time, the same way as `kill_count`.
+#[derive(Clone, Default, Debug)]
+struct KillCounter {
+    /// Drops logged on their own.
+    solo: usize,
+    kill_ids: HashSet<u32>,
+}
+
+impl KillCounter {
+    fn add(&mut self, kill_id: Option<u32>) {
+        match kill_id {
+            Some(kill_id) => {
+                self.kill_ids.insert(kill_id);
+            }
+            None => self.solo += 1,
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.solo + self.kill_ids.len()
+    }
+}
+
+/// A raid's part of a `DropIndex`.
+#[derive(Clone, Default, Debug)]
+struct RaidIndex {
+    /// See `raid_kills`. Kills are numbered in the order they were logged, so this is also the
+    /// number of the latest kill.
+    kills: usize,
+    kill_numbers: HashMap<u32, usize>,
+    no_drops: KillCounter,
+    chests: HashMap<ChestType, KillCounter>,
+    /// Number of the kill each item last dropped in, and the id of that kill. A host or flip chest
+    /// logged before the rest of its kill takes that kill's number once the rest is logged.
+    last_dropped: HashMap<Item, (usize, Option<u32>)>,
+}
+
+/// Drops in a row of those Recent Drops shows that are the same item from the same raid and
+/// chest, which its grid shows as one icon.
+#[derive(Clone, Debug)]
+pub struct DropRun {
+    key: (Raid, Item, ChestType),
+    /// Where the run's newest drop is in the log.
+    pub newest: usize,
+    /// Amount of the item over the whole run.
+    pub quantity: u32,
+}
+
+/// Every count the Drop Totals, item buttons, overlay and Recent Drops show, kept up to date as
+/// drops are logged so they don't go through the whole log every frame. Drops are added to the
+/// index one at a time. Removing a drop can renumber every kill after it, so removals rebuild it.
+#[derive(Clone, Default, Debug)]
+pub struct DropIndex {
+    /// Drops indexed so far.
+    len: usize,
+    raids: HashMap<Raid, RaidIndex>,
+    hosts: KillCounter,
+    /// Amount of each item and the kills it dropped from, see `item_count` and `drop_count`.
+    items: HashMap<(Raid, Item, ChestType), (usize, usize)>,
+    /// Where the drops Recent Drops shows are in the log, oldest first.
+    shown: Vec<usize>,
+    /// Runs of the drops in `shown`, oldest first.
+    runs: Vec<DropRun>,
+    next_drop_id: u32,
+    next_kill_id: u32,
+}
+
+impl DropIndex {
+    pub fn new(drops: &[ItemDrop]) -> Self {
+        let mut index = Self::default();
+        index.update(drops);
+        index
+    }
+
+    /// Indexes the drops logged since the last update, or rebuilds the index if the log is shorter
+    /// than it.
+    pub fn update(&mut self, drops: &[ItemDrop]) {
+        if drops.len() < self.len {
+            *self = Self::default();
+        }
+        for drop in &drops[self.len..] {
+            self.push(drop);
+        }
+    }
+
+    fn push(&mut self, drop: &ItemDrop) {
+        if drop.item != Item::NoDrop {
+            self.shown.push(self.len);
+            let key = (drop.raid, drop.item, drop.chest);
+            match self.runs.last_mut() {
+                Some(run) if run.key == key => {
+                    run.newest = self.len;
+                    run.quantity = run.quantity.saturating_add(drop.quantity);
+                }
+                _ => self.runs.push(DropRun {
+                    key,
+                    newest: self.len,
+                    quantity: drop.quantity,
+                }),
+            }
+        }
+        self.len += 1;
+        let totals = self
+            .items
+            .entry((drop.raid, drop.item, drop.chest))
+            .or_default();
+        totals.0 += drop.quantity as usize;
+        totals.1 += 1;
+        if is_hosts_drop(drop) {
+            self.hosts.add(drop.kill_id);
+        }
+        self.next_drop_id = self.next_drop_id.max(drop.drop_id.saturating_add(1));
+        if let Some(kill_id) = drop.kill_id {
+            self.next_kill_id = self.next_kill_id.max(kill_id + 1);
+        }
+
+        let raid = self.raids.entry(drop.raid).or_default();
+        let kill_number = if is_host_or_flip(drop.chest) {
+            raid.kills
+        } else {
+            match drop.kill_id {
+                Some(kill_id) => *raid.kill_numbers.entry(kill_id).or_insert_with(|| {
+                    raid.kills += 1;
+                    raid.kills
+                }),
+                None => {
+                    raid.kills += 1;
+                    raid.kills
+                }
+            }
+        };
+        if drop.item == Item::NoDrop {
+            raid.no_drops.add(drop.kill_id);
+        } else {
+            raid.chests.entry(drop.chest).or_default().add(drop.kill_id);
+        }
+        raid.last_dropped
+            .insert(drop.item, (kill_number, drop.kill_id));
+    }
+
+    /// Where the drops Recent Drops shows are in the log, oldest first. No Drop isn't shown.
+    pub fn shown(&self) -> &[usize] {
+        &self.shown
+    }
+
+    /// Runs of the drops Recent Drops shows, oldest first, see `DropRun`.
+    pub fn runs(&self) -> &[DropRun] {
+        &self.runs
+    }
+
+    /// Id for the next drop logged, one past the highest so far. Drops removed from the middle of
+    /// the log would make the log's length an id that's taken.
+    pub fn next_drop_id(&self) -> u32 {
+        self.next_drop_id
+    }
+
+    /// Id for the next kill logged, one past the highest so far.
+    pub fn next_kill_id(&self) -> u32 {
+        self.next_kill_id
+    }
+
+    /// See `raid_kills`.
+    pub fn raid_kills(&self, raid: Raid) -> usize {
+        self.raids.get(&raid).map_or(0, |x| x.kills)
+    }
+
+    /// See `chest_kills`.
+    pub fn chest_kills(&self, raid: Raid, chest: ChestType) -> usize {
+        self.raids
+            .get(&raid)
+            .and_then(|x| x.chests.get(&chest))
+            .map_or(0, |x| x.count())
+    }
+
+    /// See `no_drop_count`.
+    pub fn no_drop_count(&self, raid: Raid) -> usize {
+        self.raids.get(&raid).map_or(0, |x| x.no_drops.count())
+    }
+
+    /// See `hosts_kills`.
+    pub fn hosts_kills(&self) -> usize {
+        self.hosts.count()
+    }
+
+    /// See `total_kills`.
+    pub fn total_kills(&self, raid: Raid) -> usize {
+        if raid == Raid::UBHL || raid == Raid::Xeno {
+            return self.hosts_kills();
+        }
+        self.raid_kills(raid)
+    }
+
+    /// See `RaidKills::of`.
+    pub fn kills(&self, raid: Raid) -> RaidKills {
+        RaidKills {
+            kills: self.raid_kills(raid),
+            no_drops: self.no_drop_count(raid),
+            blue: self.chest_kills(raid, ChestType::Blue),
+            host: self.chest_kills(raid, ChestType::Host),
+            flip: self.chest_kills(raid, ChestType::Flip),
+            mvp: self.chest_kills(raid, ChestType::Mvp),
+            gold: self.chest_kills(raid, ChestType::Gold),
+        }
+    }
+
+    /// See `item_count`.
+    pub fn item_count(&self, raid: Raid, item: Item, chest: ChestType) -> usize {
+        self.items.get(&(raid, item, chest)).map_or(0, |x| x.0)
+    }
+
+    /// See `drop_count`.
+    pub fn drop_count(&self, raid: Raid, item: Item, chest: ChestType) -> usize {
+        self.items.get(&(raid, item, chest)).map_or(0, |x| x.1)
+    }
+
+    /// See `dry_streak`.
+    pub fn dry_streak(&self, raid: Raid, item: Item) -> usize {
+        let raid = match self.raids.get(&raid) {
+            Some(raid) => raid,
+            None => return 0,
+        };
+        let last_kill = match raid.last_dropped.get(&item) {
+            Some(&(kill_number, kill_id)) => kill_id
+                .and_then(|kill_id| raid.kill_numbers.get(&kill_id).copied())
+                .unwrap_or(kill_number),
+            None => 0,
+        };
+        raid.kills - last_kill
+    }
+
+    /// See `drop_rate`.
+    pub fn drop_rate(
+        &self,
+        raid: Raid,
+        item: Item,
+        chest: ChestType,
+        droprate_by_kills: bool,
+    ) -> Option<f32> {
+        rate(self, raid, item, chest, droprate_by_kills)
+    }
+}
+
+impl DropCounts for DropIndex {
+    fn drop_count(&self, raid: Raid, item: Item, chest: ChestType) -> usize {
+        self.drop_count(raid, item, chest)
+    }
+
+    fn raid_kills(&self, raid: Raid) -> usize {
+        self.raid_kills(raid)
+    }
+
+    fn no_drop_count(&self, raid: Raid) -> usize {
+        self.no_drop_count(raid)
+    }
+
+    fn hosts_kills(&self) -> usize {
+        self.hosts_kills()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AppSettings, PBHLHonors};
+
+    #[test]
+    fn kill_count_counts_kill_ids_once_and_legacy_drops_each() {
+        let drops = vec![
+            ItemDrop::of(Raid::Akasha, Item::GoldBrick, ChestType::Blue, Some(1)),
+            ItemDrop::of(Raid::Akasha, Item::ChampionMerit, ChestType::Gold, Some(1)),
+            ItemDrop::of(Raid::Akasha, Item::SupremeMerit, ChestType::Mvp, Some(1)),
+            ItemDrop::of(Raid::Akasha, Item::NoDrop, ChestType::None, Some(2)),
+            ItemDrop::of(Raid::Akasha, Item::NoDrop, ChestType::None, None),
+            ItemDrop::of(Raid::Akasha, Item::NoDrop, ChestType::None, None),
+        ];
+        assert_eq!(kill_count(&drops), 4);
+        assert_eq!(kill_count(&drops[..3]), 1);
+        assert_eq!(kill_count(&drops[4..]), 2);
+        assert_eq!(kill_count(&[]), 0);
+    }
+
+    #[test]
+    fn raid_kills_leave_out_host_and_flip_chests() {
+        let drops = vec![
+            ItemDrop::of(Raid::UBHL, Item::ChampionMerit, ChestType::Blue, Some(1)),
+            ItemDrop::of(Raid::UBHL, Item::GoldBrick, ChestType::Host, Some(1)),
+            ItemDrop::of(Raid::UBHL, Item::GoldBrick, ChestType::Flip, Some(2)),
+            ItemDrop::of(Raid::UBHL, Item::GoldBrick, ChestType::Host, None),
+            ItemDrop::of(Raid::UBHL, Item::NoDrop, ChestType::None, None),
+            ItemDrop::of(Raid::PBHL, Item::GoldBrick, ChestType::Host, None),
+        ];
+        assert_eq!(raid_kills(&drops, Raid::UBHL), 2);
+        assert_eq!(raid_kills(&drops, Raid::PBHL), 0);
+        assert_eq!(chest_kills(&drops, Raid::UBHL, ChestType::Host), 2);
+        assert_eq!(chest_kills(&drops, Raid::UBHL, ChestType::None), 0);
+        assert_eq!(no_drop_count(&drops, Raid::UBHL), 1);
+    }
+
+    #[test]
+    fn hosts_kills_count_hosts_raids_and_pbhl_host_chests() {
+        let drops = vec![
+            ItemDrop::of(Raid::UBHL, Item::ChampionMerit, ChestType::Blue, Some(1)),
+            ItemDrop::of(Raid::UBHL, Item::GoldBrick, ChestType::Host, Some(1)),
+            ItemDrop::of(Raid::Xeno, Item::GoldBrick, ChestType::Flip, None),
+            ItemDrop::of(Raid::Huanglong, Item::GoldBrick, ChestType::Host, None),
+            ItemDrop::of(Raid::PBHL, Item::GoldBrick, ChestType::Host, Some(2)),
+            ItemDrop::of(Raid::PBHL, Item::GoldBrick, ChestType::Blue, Some(3)),
+            ItemDrop::of(Raid::Akasha, Item::GoldBrick, ChestType::Blue, None),
+        ];
+        assert_eq!(hosts_kills(&drops), 4);
+        assert_eq!(total_kills(&drops, Raid::UBHL), 4);
+        assert_eq!(total_kills(&drops, Raid::Xeno), 4);
+        assert_eq!(total_kills(&drops, Raid::PBHL), 1);
+    }
+
+    #[test]
+    fn drop_rate_divides_by_the_kills_each_chest_is_rolled_in() {
+        let drops = vec![
+            ItemDrop::of(Raid::Akasha, Item::GoldBrick, ChestType::Blue, Some(1)),
+            ItemDrop::of(Raid::Akasha, Item::ChampionMerit, ChestType::Gold, Some(1)),
+            ItemDrop::of(Raid::Akasha, Item::HollowKey, ChestType::Blue, Some(2)),
+            ItemDrop::of(Raid::Akasha, Item::ChampionMerit, ChestType::Gold, Some(2)),
+            ItemDrop::of(Raid::Akasha, Item::NoDrop, ChestType::None, Some(3)),
+            ItemDrop::of(Raid::Akasha, Item::NoDrop, ChestType::None, None),
+            ItemDrop::of(Raid::UBHL, Item::GoldBrick, ChestType::Host, None),
+            ItemDrop::of(Raid::UBHL, Item::ChampionMerit, ChestType::Blue, None),
+        ];
+        let rate = |raid, item, chest, by_kills| drop_rate(&drops, raid, item, chest, by_kills);
+        let blue = ChestType::Blue;
+        assert_eq!(rate(Raid::Akasha, Item::GoldBrick, blue, false), Some(0.5));
+        assert_eq!(rate(Raid::Akasha, Item::GoldBrick, blue, true), Some(0.25));
+        let gold = ChestType::Gold;
+        assert_eq!(
+            rate(Raid::Akasha, Item::ChampionMerit, gold, false),
+            Some(0.5)
+        );
+        let none = ChestType::None;
+        assert_eq!(rate(Raid::Akasha, Item::NoDrop, none, false), None);
+        assert_eq!(rate(Raid::Akasha, Item::NoDrop, none, true), Some(0.5));
+        let host = ChestType::Host;
+        assert_eq!(rate(Raid::UBHL, Item::GoldBrick, host, false), Some(0.5));
+        assert_eq!(rate(Raid::Akasha, Item::LineageRing, blue, true), None);
+    }
+
+    #[test]
+    fn logged_drops_get_ids_past_the_highest() {
+        let honors = PBHLHonors::Ignore;
+        let mut settings = AppSettings::default();
+        settings.log_drops(Item::GoldBrick, Raid::UBHL, ChestType::Blue, &honors, 3, 1);
+        settings.remove_drop(1);
+        settings.log_drops(Item::GoldBrick, Raid::UBHL, ChestType::Blue, &honors, 1, 1);
+        let ids: Vec<_> = settings.droplog.drop.iter().map(|x| x.drop_id).collect();
+        assert_eq!(ids, [0, 2, 3]);
+        assert_eq!(settings.stats.next_drop_id(), 4);
+        settings.remove_drop(3);
+        assert_eq!(settings.droplog.drop.len(), 2);
+    }
+
+    #[test]
+    fn undo_removes_the_last_kill_or_batch() {
+        let honors = PBHLHonors::Ignore;
+        let mut settings = AppSettings::default();
+        settings.log_drop(Item::GoldBrick, Raid::UBHL, ChestType::Blue, &honors);
+        settings.log_drops(Item::NoDrop, Raid::UBHL, ChestType::None, &honors, 3, 1);
+        settings.undo_last_drop();
+        assert_eq!(settings.droplog.drop.len(), 1);
+        settings.undo_last_drop();
+        assert!(settings.droplog.drop.is_empty());
+
+        settings.log_drop(Item::GoldBrick, Raid::UBHL, ChestType::Blue, &honors);
+        settings.kill = vec![
+            (Item::ChampionMerit, Raid::UBHL, ChestType::Blue),
+            (Item::GoldBrick, Raid::UBHL, ChestType::Host),
+        ];
+        settings.log_kill(&honors);
+        let ids: Vec<_> = settings.droplog.drop.iter().map(|x| x.drop_id).collect();
+        assert_eq!(ids, [0, 1, 2]);
+        settings.undo_last_drop();
+        assert_eq!(settings.droplog.drop.len(), 1);
+
+        // Without a batch to go by, as after a restart, the kill id still groups a kill's drops
+        settings.kill = vec![
+            (Item::ChampionMerit, Raid::UBHL, ChestType::Blue),
+            (Item::GoldBrick, Raid::UBHL, ChestType::Host),
+        ];
+        settings.log_kill(&honors);
+        settings.last_logged = 0..0;
+        settings.undo_last_drop();
+        assert_eq!(settings.droplog.drop.len(), 1);
+    }
+
+    /// Checks the index kept up to date while logging, and one built from scratch, against the
+    /// functions working on the log itself.
+    fn assert_index_matches_log(settings: &AppSettings) {
+        let drops = &settings.droplog.drop;
+        let mut runs: Vec<((Raid, Item, ChestType), usize, u32)> = vec![];
+        for (pos, drop) in drops.iter().enumerate() {
+            let key = (drop.raid, drop.item, drop.chest);
+            match runs.last_mut() {
+                _ if drop.item == Item::NoDrop => {}
+                Some(run) if run.0 == key => *run = (key, pos, run.2 + drop.quantity),
+                _ => runs.push((key, pos, drop.quantity)),
+            }
+        }
+        for index in [&settings.stats, &DropIndex::new(drops)] {
+            let index_runs: Vec<_> = index
+                .runs()
+                .iter()
+                .map(|run| (run.key, run.newest, run.quantity))
+                .collect();
+            assert_eq!(index_runs, runs);
+            assert_eq!(index.hosts_kills(), hosts_kills(drops));
+            for raid in Raid::ALL {
+                assert_eq!(index.raid_kills(raid), raid_kills(drops, raid));
+                assert_eq!(index.total_kills(raid), total_kills(drops, raid));
+                assert_eq!(index.kills(raid), RaidKills::of(drops, raid));
+                for item in Item::ALL {
+                    assert_eq!(
+                        index.dry_streak(raid, item),
+                        dry_streak(drops, raid, item),
+                        "dry streak of {:?} from {:?}",
+                        item,
+                        raid
+                    );
+                    for chest in ChestType::ALL {
+                        assert_eq!(
+                            index.item_count(raid, item, chest),
+                            item_count(drops, raid, item, chest)
+                        );
+                        assert_eq!(
+                            index.drop_count(raid, item, chest),
+                            drop_count(drops, raid, item, chest)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn drop_index_matches_the_log() {
+        let honors = PBHLHonors::Ignore;
+        let mut settings = AppSettings::default();
+        settings.log_drops(Item::NoDrop, Raid::Akasha, ChestType::None, &honors, 3, 1);
+        settings.log_drops(
+            Item::EternitySand,
+            Raid::Wilnas,
+            ChestType::Gold,
+            &honors,
+            2,
+            3,
+        );
+        settings.kill = vec![
+            (Item::GoldBrick, Raid::Akasha, ChestType::Blue),
+            (Item::ChampionMerit, Raid::Akasha, ChestType::Gold),
+        ];
+        settings.log_kill(&honors);
+        settings.kill = vec![
+            (Item::ChampionMerit, Raid::UBHL, ChestType::Blue),
+            (Item::GoldBrick, Raid::UBHL, ChestType::Host),
+        ];
+        settings.log_kill(&honors);
+        settings.log_drops(Item::GoldBrick, Raid::PBHL, ChestType::Host, &honors, 1, 1);
+        settings.log_drops(Item::NoDrop, Raid::Akasha, ChestType::None, &honors, 2, 1);
+        assert_index_matches_log(&settings);
+
+        settings.remove_drop(settings.droplog.drop[1].drop_id);
+        assert_index_matches_log(&settings);
+        settings.log_drops(
+            Item::HollowKey,
+            Raid::Akasha,
+            ChestType::Blue,
+            &honors,
+            1,
+            1,
+        );
+        assert_index_matches_log(&settings);
+
+        let mut imported = vec![
+            ItemDrop::new(
+                0,
+                "2020-01-01 12:00:00".to_string(),
+                Raid::Akasha,
+                Item::GoldBrick,
+                ChestType::Blue,
+                None,
+            ),
+            ItemDrop::new(
+                1,
+                "2020-01-01 12:00:00".to_string(),
+                Raid::Akasha,
+                Item::NoDrop,
+                ChestType::None,
+                None,
+            ),
+        ];
+        imported[0].kill_id = Some(1);
+        settings.import_drops(imported);
+        assert_index_matches_log(&settings);
+        settings.kill = vec![(Item::GoldBrick, Raid::Akasha, ChestType::Blue)];
+        settings.log_kill(&honors);
+        assert_index_matches_log(&settings);
+    }
+}
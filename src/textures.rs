@@ -0,0 +1,40 @@
+use crate::{item_image, item_texture_name, load_image_from_memory, ChestType, Item, Raid};
+use eframe::egui::{self, TextureHandle, TextureId};
+use std::sync::{Arc, Mutex};
+
+type ItemTexture = ((Item, Raid, ChestType), TextureHandle);
+
+/// Item icons uploaded to an `egui::Context`. Each icon is decoded the first time it's shown and
+/// kept for as long as the context, so every panel shares the same textures.
+#[derive(Clone, Default)]
+pub struct ItemTextures(Arc<Mutex<Vec<ItemTexture>>>);
+
+impl ItemTextures {
+    /// The textures of `ctx`, starting out empty.
+    pub fn of(ctx: &egui::Context) -> Self {
+        // Cloned out so `ctx` isn't locked while an icon is uploaded.
+        ctx.data()
+            .get_temp_mut_or_default::<ItemTextures>(egui::Id::new("item_textures"))
+            .clone()
+    }
+
+    pub fn get(&self, ctx: &egui::Context, item: Item, raid: Raid, chest: ChestType) -> TextureId {
+        let mut textures = self.0.lock().unwrap();
+        let key = (item, raid, chest);
+        if let Some((_, texture)) = textures.iter().find(|(x, _)| *x == key) {
+            return texture.id();
+        }
+        let texture = ctx.load_texture(
+            item_texture_name(item),
+            load_image_from_memory(item_image(item, raid, chest)).unwrap(),
+        );
+        let id = texture.id();
+        textures.push((key, texture));
+        id
+    }
+}
+
+/// Texture of the icon `item_image` gives the item.
+pub fn item_texture(ctx: &egui::Context, item: Item, raid: Raid, chest: ChestType) -> TextureId {
+    ItemTextures::of(ctx).get(ctx, item, raid, chest)
+}
@@ -0,0 +1,398 @@
+use crate::{export, DorothyConfig};
+use eframe::egui::{self, Color32, FontFamily, Ui, Visuals};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+pub const DARK_THEME: &str = "Dark";
+pub const LIGHT_THEME: &str = "Light";
+
+/// Which of egui's font families text is drawn in. A theme's font file goes first in it.
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum FontStyle {
+    #[default]
+    Proportional,
+    Monospace,
+}
+
+impl FontStyle {
+    pub const ALL: [FontStyle; 2] = [FontStyle::Proportional, FontStyle::Monospace];
+
+    pub fn family(&self) -> FontFamily {
+        match *self {
+            FontStyle::Proportional => FontFamily::Proportional,
+            FontStyle::Monospace => FontFamily::Monospace,
+        }
+    }
+}
+
+impl fmt::Display for FontStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            FontStyle::Proportional => write!(f, "Proportional"),
+            FontStyle::Monospace => write!(f, "Monospace"),
+        }
+    }
+}
+
+/// Colors and fonts of the window. Colors are sRGB.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub name: String,
+    /// Whether the theme starts from egui's dark or light visuals.
+    pub dark: bool,
+    /// Selected buttons and selected text.
+    pub accent: [u8; 3],
+    /// Panels and windows.
+    pub background: [u8; 3],
+    pub gold_bar: [u8; 3],
+    pub eternity_sand: [u8; 3],
+    pub earring: [u8; 3],
+    pub font_style: FontStyle,
+    /// Path of a .ttf or .otf file to draw text in, empty for egui's own fonts. Only read on
+    /// desktop.
+    pub font_file: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+fn srgb(color: Color32) -> [u8; 3] {
+    [color.r(), color.g(), color.b()]
+}
+
+fn color([r, g, b]: [u8; 3]) -> Color32 {
+    Color32::from_rgb(r, g, b)
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        let visuals = Visuals::dark();
+        Self {
+            name: DARK_THEME.to_string(),
+            dark: true,
+            accent: srgb(visuals.selection.bg_fill),
+            background: srgb(visuals.widgets.noninteractive.bg_fill),
+            gold_bar: [255, 221, 26],
+            eternity_sand: [235, 120, 70],
+            earring: [130, 200, 255],
+            font_style: FontStyle::Proportional,
+            font_file: String::new(),
+        }
+    }
+
+    pub fn light() -> Self {
+        let visuals = Visuals::light();
+        Self {
+            name: LIGHT_THEME.to_string(),
+            dark: false,
+            accent: srgb(visuals.selection.bg_fill),
+            background: srgb(visuals.widgets.noninteractive.bg_fill),
+            gold_bar: [187, 152, 10],
+            eternity_sand: [190, 80, 30],
+            earring: [20, 110, 180],
+            font_style: FontStyle::Proportional,
+            font_file: String::new(),
+        }
+    }
+
+    pub fn is_built_in(name: &str) -> bool {
+        name == DARK_THEME || name == LIGHT_THEME
+    }
+
+    pub fn visuals(&self) -> Visuals {
+        let mut visuals = if self.dark {
+            Visuals::dark()
+        } else {
+            Visuals::light()
+        };
+        visuals.selection.bg_fill = color(self.accent);
+        visuals.widgets.noninteractive.bg_fill = color(self.background);
+        visuals
+    }
+
+    pub fn gold_bar_color(&self) -> Color32 {
+        color(self.gold_bar)
+    }
+
+    pub fn eternity_sand_color(&self) -> Color32 {
+        color(self.eternity_sand)
+    }
+
+    pub fn earring_color(&self) -> Color32 {
+        color(self.earring)
+    }
+
+    /// egui's fonts with `font_file` ahead of the others in the font style's family.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn font_definitions(&self) -> Result<egui::FontDefinitions, Box<dyn Error>> {
+        let mut fonts = egui::FontDefinitions::default();
+        let font_file = self.font_file.trim();
+        if font_file.is_empty() {
+            return Ok(fonts);
+        }
+        let font = std::fs::read(font_file)?;
+        // egui panics on fonts it can't read
+        ab_glyph::FontRef::try_from_slice(&font)?;
+        fonts
+            .font_data
+            .insert(font_file.to_string(), egui::FontData::from_owned(font));
+        fonts
+            .families
+            .entry(self.font_style.family())
+            .or_default()
+            .insert(0, font_file.to_string());
+        Ok(fonts)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// `name`, or `name` with the first number after it that no theme is called yet.
+pub fn unique_theme_name(themes: &[Theme], name: &str) -> String {
+    let name = name.trim();
+    let name = if name.is_empty() {
+        "Custom Theme"
+    } else {
+        name
+    };
+    let taken = |candidate: &str| {
+        Theme::is_built_in(candidate) || themes.iter().any(|x| x.name == candidate)
+    };
+    if !taken(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{} {}", name, n))
+        .find(|candidate| !taken(candidate))
+        .unwrap()
+}
+
+impl DorothyConfig {
+    /// The custom theme in use, or the built-in dark or light theme.
+    pub fn active_theme(&self) -> Theme {
+        self.theme
+            .as_ref()
+            .and_then(|name| self.themes.iter().find(|x| &x.name == name))
+            .cloned()
+            .unwrap_or_else(|| {
+                if self.dark_mode {
+                    Theme::dark()
+                } else {
+                    Theme::light()
+                }
+            })
+    }
+
+    /// Adds `theme` under a name no other theme has and switches to it.
+    pub fn add_theme(&mut self, mut theme: Theme) {
+        theme.name = unique_theme_name(&self.themes, &theme.name);
+        self.dark_mode = theme.dark;
+        self.theme = Some(theme.name.clone());
+        self.themes.push(theme);
+    }
+}
+
+/// State of the Themes window.
+#[derive(Default)]
+pub struct ThemeEditor {
+    pub open: bool,
+    /// Name being typed for the theme called `renaming`, kept apart from the theme until it's a
+    /// name the theme can have.
+    pub new_name: String,
+    pub renaming: String,
+    /// Path of a theme file to import.
+    pub file_path: String,
+    pub status: Option<String>,
+    /// Font file and style egui's fonts were last set from, see `apply_font`.
+    #[cfg(not(target_arch = "wasm32"))]
+    loaded_font: Option<(String, FontStyle)>,
+    #[cfg(target_arch = "wasm32")]
+    pub pending: export::PendingImport,
+}
+
+impl ThemeEditor {
+    pub fn import(&mut self, settings: &mut DorothyConfig, file_name: &str, contents: &[u8]) {
+        self.status = Some(match Theme::from_bytes(contents) {
+            Ok(theme) => {
+                settings.add_theme(theme);
+                format!("Imported {}.", settings.theme.as_ref().unwrap())
+            }
+            Err(e) => format!("Dorothy couldn't read {}: {}", file_name, e),
+        });
+    }
+
+    /// Sets egui's fonts from `theme` whenever its font file or style changes. A font file that
+    /// can't be loaded leaves egui's own fonts and says why.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn apply_font(&mut self, theme: &Theme, ctx: &egui::Context) {
+        let font = (theme.font_file.trim().to_string(), theme.font_style);
+        if self.loaded_font.as_ref() == Some(&font) {
+            return;
+        }
+        ctx.set_fonts(theme.font_definitions().unwrap_or_else(|e| {
+            self.status = Some(format!("Dorothy couldn't load {}: {}", font.0, e));
+            egui::FontDefinitions::default()
+        }));
+        self.loaded_font = Some(font);
+    }
+}
+
+fn place_color(label: &str, srgb: &mut [u8; 3], ui: &mut Ui) {
+    ui.label(label);
+    ui.color_edit_button_srgb(srgb);
+    ui.end_row();
+}
+
+/// Picking, editing, importing and exporting themes. The built-in Dark and Light themes can't be
+/// edited, only copied into a new theme.
+pub fn place_theme_editor(editor: &mut ThemeEditor, settings: &mut DorothyConfig, ui: &mut Ui) {
+    let active_theme = settings.active_theme();
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_label("Theme")
+            .selected_text(&active_theme.name)
+            .show_ui(ui, |ui| {
+                for (name, dark) in [(DARK_THEME, true), (LIGHT_THEME, false)] {
+                    if ui
+                        .selectable_label(active_theme.name == name, name)
+                        .clicked()
+                    {
+                        settings.theme = None;
+                        settings.dark_mode = dark;
+                    }
+                }
+                for theme in settings.themes.clone() {
+                    if ui
+                        .selectable_label(active_theme.name == theme.name, &theme.name)
+                        .clicked()
+                    {
+                        settings.dark_mode = theme.dark;
+                        settings.theme = Some(theme.name);
+                    }
+                }
+            });
+        if ui.button("New Theme").clicked() {
+            let mut theme = active_theme.clone();
+            theme.name = format!("{} Copy", active_theme.name);
+            settings.add_theme(theme);
+        }
+    });
+    ui.separator();
+
+    match settings
+        .themes
+        .iter()
+        .position(|x| x.name == active_theme.name)
+    {
+        Some(index) => {
+            if editor.renaming != active_theme.name {
+                editor.renaming = active_theme.name.clone();
+                editor.new_name = active_theme.name.clone();
+            }
+            let theme = &mut settings.themes[index];
+            egui::Grid::new("theme_editor_grid").show(ui, |ui| {
+                ui.label("Name");
+                ui.text_edit_singleline(&mut editor.new_name);
+                ui.end_row();
+                ui.label("Base");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut theme.dark, true, DARK_THEME);
+                    ui.radio_value(&mut theme.dark, false, LIGHT_THEME);
+                });
+                ui.end_row();
+                place_color("Accent", &mut theme.accent, ui);
+                place_color("Background", &mut theme.background, ui);
+                place_color("Gold Bars", &mut theme.gold_bar, ui);
+                place_color("Eternity Sand", &mut theme.eternity_sand, ui);
+                place_color("Earrings", &mut theme.earring, ui);
+                ui.label("Font Style");
+                egui::ComboBox::from_id_source("theme_font_style")
+                    .selected_text(theme.font_style.to_string())
+                    .show_ui(ui, |ui| {
+                        for style in FontStyle::ALL {
+                            ui.selectable_value(&mut theme.font_style, style, style.to_string());
+                        }
+                    });
+                ui.end_row();
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.label("Font File");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut theme.font_file)
+                            .hint_text("Path to a .ttf or .otf file"),
+                    );
+                    ui.end_row();
+                }
+            });
+            settings.dark_mode = theme.dark;
+
+            let new_name = editor.new_name.trim().to_string();
+            if new_name != active_theme.name {
+                // An empty or taken name would make the theme impossible to pick.
+                if new_name.is_empty() || unique_theme_name(&settings.themes, &new_name) != new_name
+                {
+                    ui.label("Themes need a name no other theme has.");
+                } else {
+                    settings.themes[index].name = new_name.clone();
+                    settings.theme = Some(new_name.clone());
+                    editor.renaming = new_name;
+                }
+            }
+        }
+        None => {
+            ui.label("The built-in themes can't be changed. Make a New Theme to edit a copy.");
+        }
+    }
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        if ui.button("Export").clicked() {
+            let file_name = format!("dorothy-theme-{}.json", active_theme.name.replace(' ', "-"));
+            editor.status = Some(
+                match active_theme.to_bytes().and_then(|contents| {
+                    export::save_export(&file_name, "application/json", &contents)
+                }) {
+                    Ok(()) => format!("Exported {}.", file_name),
+                    Err(e) => format!("Dorothy couldn't export {}: {}", file_name, e),
+                },
+            );
+        }
+        if !Theme::is_built_in(&active_theme.name) && ui.button("Delete").clicked() {
+            settings.themes.retain(|x| x.name != active_theme.name);
+            settings.theme = None;
+        }
+    });
+    ui.horizontal(|ui| {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.label("Import");
+            ui.text_edit_singleline(&mut editor.file_path);
+            if ui.button("Load").clicked() {
+                let file_path = editor.file_path.trim().to_string();
+                match std::fs::read(&file_path) {
+                    Ok(contents) => editor.import(settings, &file_path, &contents),
+                    Err(e) => {
+                        editor.status = Some(format!("Dorothy couldn't open {}: {}", file_path, e))
+                    }
+                }
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        if ui.button("Import").clicked() {
+            let _ = export::pick_import_file(editor.pending.clone(), ".json");
+        }
+    });
+    if let Some(status) = &editor.status {
+        ui.label(status);
+    }
+}
@@ -0,0 +1,874 @@
+use eframe::egui::{self, Ui};
+use self_update::cargo_crate_version;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::{env, fs, thread};
+
+/// File a mirror lists its releases in, next to the executables.
+pub const MIRROR_INDEX: &str = "releases.json";
+/// File listing the SHA-256 checksums of every file in a release, as written by `sha256sum`.
+/// An executable's checksum can also be published on its own as `<executable>.sha256`.
+pub const CHECKSUMS_FILE: &str = "SHA256SUMS";
+/// Extension of an executable's minisign signature, `<executable>.minisig`.
+pub const SIGNATURE_EXTENSION: &str = "minisig";
+
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum UpdateSource {
+    #[default]
+    GitHub,
+    /// A local directory or a file server, see `MirrorRelease`.
+    Mirror,
+}
+
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    /// Stable releases and pre-releases, which have a pre-release version like `0.1.0-beta.1`.
+    PreRelease,
+}
+
+/// Where Dorothy looks for updates. Forks can point this at their own releases.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateSettings {
+    pub source: UpdateSource,
+    pub channel: UpdateChannel,
+    pub repo_owner: String,
+    pub repo_name: String,
+    /// Name of the executable in GitHub releases, without the target or extension.
+    pub bin_name: String,
+    /// Directory path or http(s) URL of a mirror.
+    pub mirror: String,
+    /// Minisign public key updates have to be signed with. Updates aren't checked for a signature
+    /// when it's empty, only for a checksum.
+    pub public_key: String,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            source: UpdateSource::GitHub,
+            channel: UpdateChannel::Stable,
+            repo_owner: "NadyaNayme".to_string(),
+            repo_name: "Dorothy-egui".to_string(),
+            bin_name: "dorothy_egui_bin".to_string(),
+            mirror: String::new(),
+            public_key: String::new(),
+        }
+    }
+}
+
+/// A release listed in a mirror's `releases.json`, e.g.
+/// `[{ "version": "0.0.20", "name": "Dorothy 0.0.20", "notes": "...", "file": "dorothy_egui_bin.exe" }]`.
+/// `file` is relative to the mirror. Mirrors have to publish its checksum next to it, in
+/// `SHA256SUMS` or `<file>.sha256`, and its signature in `<file>.minisig` for anyone who set a
+/// public key, the same as GitHub releases do. Updates without them are refused.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct MirrorRelease {
+    pub version: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub notes: String,
+    pub file: String,
+}
+
+/// A release newer than the running Dorothy.
+#[derive(PartialEq, Clone, Debug)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub name: String,
+    /// The release's description, which is where its changelog goes.
+    pub notes: String,
+    /// The release's executable, next to its checksum and signature. `None` when a GitHub
+    /// release doesn't have one for this platform.
+    pub file: Option<String>,
+    /// Name and download URL of every file in a GitHub release. Mirrors don't have any, their
+    /// files are found by name.
+    pub assets: Vec<(String, String)>,
+}
+
+#[derive(PartialEq, Clone, Default, Debug)]
+pub enum UpdateStatus {
+    #[default]
+    Idle,
+    Checking,
+    UpToDate,
+    Available(ReleaseInfo),
+    Downloading(DownloadProgress),
+    /// The new version replaced the executable, which takes a restart to run.
+    Installed(String),
+    RolledBack,
+    Failed(String),
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct DownloadProgress {
+    pub version: String,
+    /// Bytes downloaded so far.
+    pub downloaded: u64,
+    /// Size of the download, which file servers don't always say.
+    pub total: Option<u64>,
+}
+
+impl DownloadProgress {
+    pub fn fraction(&self) -> Option<f32> {
+        match self.total {
+            Some(total) if total > 0 => Some((self.downloaded as f32 / total as f32).min(1.)),
+            _ => None,
+        }
+    }
+}
+
+/// Collects a download while reporting how much of it has arrived.
+struct ProgressWriter<'a> {
+    contents: Vec<u8>,
+    total: Option<u64>,
+    on_progress: &'a mut dyn FnMut(u64, Option<u64>),
+}
+
+impl Write for ProgressWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.contents.extend_from_slice(buf);
+        (self.on_progress)(self.contents.len() as u64, self.total);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn download(
+    url: &str,
+    on_progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    // GitHub's asset URLs answer with the asset's details unless the file itself is asked for,
+    // and turn away requests without a user agent
+    let mut response = reqwest::blocking::Client::new()
+        .get(url)
+        .header(reqwest::header::ACCEPT, "application/octet-stream")
+        .header(reqwest::header::USER_AGENT, "dorothy-egui")
+        .send()?
+        .error_for_status()?;
+    let mut writer = ProgressWriter {
+        contents: vec![],
+        total: response.content_length(),
+        on_progress,
+    };
+    response.copy_to(&mut writer)?;
+    Ok(writer.contents)
+}
+
+pub fn is_pre_release(version: &str) -> bool {
+    version.trim_start_matches('v').contains('-')
+}
+
+/// The newest release on `channel` that's newer than `current_version`.
+pub fn newest_release(
+    releases: Vec<ReleaseInfo>,
+    current_version: &str,
+    channel: UpdateChannel,
+) -> Result<Option<ReleaseInfo>, Box<dyn Error>> {
+    let mut newest: Option<ReleaseInfo> = None;
+    for release in releases {
+        if channel == UpdateChannel::Stable && is_pre_release(&release.version) {
+            continue;
+        }
+        let newest_version = newest.as_ref().map_or(current_version, |x| &x.version);
+        if self_update::version::bump_is_greater(newest_version, &release.version)? {
+            newest = Some(release);
+        }
+    }
+    Ok(newest)
+}
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+/// Reads `file` from a mirror, over HTTP if the mirror is a URL.
+fn read_from_mirror(
+    mirror: &str,
+    file: &str,
+    on_progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    if mirror.trim().is_empty() {
+        return Err("no mirror has been set".into());
+    }
+    if is_url(mirror) {
+        return download(
+            &format!("{}/{}", mirror.trim_end_matches('/'), file),
+            on_progress,
+        );
+    }
+    let mut source = fs::File::open(Path::new(mirror).join(file))?;
+    let mut writer = ProgressWriter {
+        contents: vec![],
+        total: Some(source.metadata()?.len()),
+        on_progress,
+    };
+    io::copy(&mut source, &mut writer)?;
+    Ok(writer.contents)
+}
+
+/// Reads `file` from the GitHub release or mirror `release` came from.
+fn read_release_file(
+    settings: &UpdateSettings,
+    release: &ReleaseInfo,
+    file: &str,
+    on_progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    match settings.source {
+        UpdateSource::GitHub => {
+            let url = release
+                .assets
+                .iter()
+                .find(|(name, _)| name == file)
+                .map(|(_, url)| url)
+                .ok_or_else(|| format!("the release doesn't have {}", file))?;
+            download(url, on_progress)
+        }
+        UpdateSource::Mirror => read_from_mirror(&settings.mirror, file, on_progress),
+    }
+}
+
+fn is_checksum_or_signature(asset: &str) -> bool {
+    asset == CHECKSUMS_FILE
+        || asset.ends_with(".sha256")
+        || asset.ends_with(&format!(".{}", SIGNATURE_EXTENSION))
+}
+
+/// Whether a file is an archive rather than an executable. Dorothy can only update from the
+/// executable itself.
+pub fn is_archive(file: &str) -> bool {
+    let file = file.to_lowercase();
+    [
+        ".zip", ".tar", ".tar.gz", ".tgz", ".tar.xz", ".txz", ".tar.bz2", ".gz", ".xz", ".7z",
+    ]
+    .iter()
+    .any(|extension| file.ends_with(extension))
+}
+
+/// Names operating systems go by in targets and file names.
+const OS_NAMES: [(&str, &[&str]); 3] = [
+    ("windows", &["windows"]),
+    ("macos", &["apple", "darwin", "macos"]),
+    ("linux", &["linux"]),
+];
+
+/// The names of the operating system `target` is for, e.g. `["windows"]` for
+/// `x86_64-pc-windows-msvc`.
+fn os_names(target: &str) -> &'static [&'static str] {
+    OS_NAMES
+        .iter()
+        .find(|(_, names)| names.iter().any(|name| target.contains(name)))
+        .map_or(&[], |(_, names)| names)
+}
+
+/// The executable among a GitHub release's assets for `target`, named like
+/// `dorothy_egui_bin-x86_64-pc-windows-msvc.exe`, then one for the same architecture and
+/// operating system built for another target, like `x86_64-pc-windows-gnu`, then the only one
+/// named after `bin_name` that doesn't name an operating system.
+/// Archives, checksums and signatures are never picked.
+pub fn release_asset(assets: &[String], bin_name: &str, target: &str) -> Option<String> {
+    let executables = || {
+        assets
+            .iter()
+            .filter(|x| !is_checksum_or_signature(x) && !is_archive(x))
+    };
+    let arch = target.split('-').next().unwrap_or(target);
+    let names_os = |asset: &str, names: &[&str]| names.iter().any(|name| asset.contains(name));
+    executables()
+        .find(|x| x.contains(target))
+        .or_else(|| {
+            executables().find(|x| {
+                !os_names(target).is_empty() && x.contains(arch) && names_os(x, os_names(target))
+            })
+        })
+        .cloned()
+        .or_else(|| {
+            let mut named = executables().filter(|x| {
+                x.starts_with(bin_name) && !OS_NAMES.iter().any(|(_, names)| names_os(x, names))
+            });
+            match (named.next(), named.next()) {
+                (Some(asset), None) => Some(asset.clone()),
+                _ => None,
+            }
+        })
+}
+
+fn github_releases(settings: &UpdateSettings) -> Result<Vec<ReleaseInfo>, Box<dyn Error>> {
+    let releases = self_update::backends::github::ReleaseList::configure()
+        .repo_owner(&settings.repo_owner)
+        .repo_name(&settings.repo_name)
+        .build()?
+        .fetch()?;
+    Ok(releases
+        .into_iter()
+        .map(|release| {
+            let names: Vec<String> = release.assets.iter().map(|x| x.name.clone()).collect();
+            ReleaseInfo {
+                file: release_asset(&names, &settings.bin_name, self_update::get_target()),
+                version: release.version,
+                name: release.name,
+                notes: release.body.unwrap_or_default(),
+                assets: release
+                    .assets
+                    .into_iter()
+                    .map(|x| (x.name, x.download_url))
+                    .collect(),
+            }
+        })
+        .collect())
+}
+
+fn mirror_releases(settings: &UpdateSettings) -> Result<Vec<ReleaseInfo>, Box<dyn Error>> {
+    let index = read_from_mirror(&settings.mirror, MIRROR_INDEX, &mut |_, _| {})?;
+    let releases: Vec<MirrorRelease> = serde_json::from_slice(&index)
+        .map_err(|e| format!("the mirror's {} couldn't be read: {}", MIRROR_INDEX, e))?;
+    Ok(releases
+        .into_iter()
+        .map(|release| ReleaseInfo {
+            version: release.version,
+            name: release.name,
+            notes: release.notes,
+            file: Some(release.file),
+            assets: vec![],
+        })
+        .collect())
+}
+
+/// The newest release if it's newer than the running Dorothy.
+pub fn latest_release(settings: &UpdateSettings) -> Result<Option<ReleaseInfo>, Box<dyn Error>> {
+    let releases = match settings.source {
+        UpdateSource::GitHub => github_releases(settings)?,
+        UpdateSource::Mirror => mirror_releases(settings)?,
+    };
+    newest_release(releases, cargo_crate_version!(), settings.channel)
+}
+
+/// `path` with `.extension` added after its own extension, e.g. `dorothy_egui_bin.exe.old`.
+fn with_added_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    path.into()
+}
+
+/// The name an executable moved aside as `<name>.old.<n>`, or `<name>.old` by older versions,
+/// had before.
+fn name_before_moved_aside(file_name: &str) -> Option<&str> {
+    let (name, n) = match file_name.rsplit_once(".old") {
+        Some((name, "")) => (name, None),
+        Some((name, n)) => (name, Some(n.strip_prefix('.')?)),
+        None => return None,
+    };
+    match n {
+        Some(n) if n.is_empty() || !n.chars().all(|x| x.is_ascii_digit()) => None,
+        _ => Some(name),
+    }
+}
+
+/// Path of Dorothy's executable. Once an update or rollback has moved the running executable
+/// aside, the running executable's path is the one it was moved to.
+fn exe_path() -> std::io::Result<PathBuf> {
+    let current_exe = env::current_exe()?;
+    let file_name = current_exe.file_name().map(|x| x.to_string_lossy());
+    match file_name.as_deref().and_then(name_before_moved_aside) {
+        Some(name) => Ok(current_exe.with_file_name(name)),
+        None => Ok(current_exe),
+    }
+}
+
+/// Where the executable is kept before an update replaces it, so the update can be rolled back.
+pub fn previous_exe_path() -> Option<PathBuf> {
+    exe_path()
+        .ok()
+        .map(|exe| with_added_extension(&exe, "previous"))
+}
+
+pub fn can_roll_back() -> bool {
+    matches!(previous_exe_path(), Some(path) if path.exists())
+}
+
+/// Puts `new_exe` in place of the running executable. A running executable can be renamed but
+/// not overwritten on Windows, so it's moved aside first and cleaned up on the next start.
+/// Every executable moved aside gets a name of its own, since the one an earlier update moved
+/// aside may be the one running.
+fn replace_exe(new_exe: &Path) -> Result<(), Box<dyn Error>> {
+    let current_exe = exe_path()?;
+    let mut n = 1;
+    let replaced_exe = loop {
+        let path = with_added_extension(&current_exe, &format!("old.{}", n));
+        if !path.exists() {
+            break path;
+        }
+        n += 1;
+    };
+    fs::rename(&current_exe, &replaced_exe)?;
+    if let Err(e) = fs::copy(new_exe, &current_exe) {
+        // Leave Dorothy where it was rather than without an executable
+        let _ = fs::rename(&replaced_exe, &current_exe);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+pub fn sha256(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+/// The checksum published for `file` in a `<file>.sha256` or `SHA256SUMS` file. Lines are a
+/// checksum followed by a file name, which a `<file>.sha256` may leave out.
+pub fn published_checksum(checksums: &str, file: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let checksum = parts.next()?;
+        match parts.next() {
+            Some(name) if name.trim_start_matches('*') != file => None,
+            _ => Some(checksum.to_lowercase()),
+        }
+    })
+}
+
+/// Refuses an executable whose checksum doesn't match the one published with it or, when
+/// there's a public key, whose signature doesn't verify.
+fn verify(
+    settings: &UpdateSettings,
+    release: &ReleaseInfo,
+    file: &str,
+    contents: &[u8],
+) -> Result<(), Box<dyn Error>> {
+    let checksum = [format!("{}.sha256", file), CHECKSUMS_FILE.to_string()]
+        .iter()
+        .filter_map(|checksums| {
+            read_release_file(settings, release, checksums, &mut |_, _| {}).ok()
+        })
+        .find_map(|checksums| published_checksum(&String::from_utf8_lossy(&checksums), file))
+        .ok_or_else(|| format!("no checksum was published for {}", file))?;
+    if sha256(contents) != checksum {
+        return Err(format!(
+            "{} doesn't match its published checksum, it may be damaged or tampered with",
+            file
+        )
+        .into());
+    }
+
+    if settings.public_key.trim().is_empty() {
+        return Ok(());
+    }
+    let public_key = minisign_verify::PublicKey::from_base64(settings.public_key.trim())
+        .map_err(|e| format!("the update public key isn't valid: {}", e))?;
+    let signature_file = format!("{}.{}", file, SIGNATURE_EXTENSION);
+    let signature = read_release_file(settings, release, &signature_file, &mut |_, _| {})
+        .map_err(|_| format!("{} isn't signed, no {} was published", file, signature_file))?;
+    let signature = minisign_verify::Signature::decode(&String::from_utf8_lossy(&signature))
+        .map_err(|e| format!("{} couldn't be read: {}", signature_file, e))?;
+    public_key
+        .verify(contents, &signature, false)
+        .map_err(|e| format!("{}'s signature doesn't match: {}", file, e))?;
+    Ok(())
+}
+
+/// Downloads `release`, checks it's the executable that was published and replaces the running
+/// one with it after keeping a copy of the current one. `on_progress` is called with the bytes
+/// downloaded so far and the size of the download if it's known.
+pub fn install(
+    settings: &UpdateSettings,
+    release: &ReleaseInfo,
+    on_progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<(), Box<dyn Error>> {
+    let file = release.file.as_ref().ok_or_else(|| {
+        format!(
+            "the release doesn't have an executable for {}",
+            self_update::get_target()
+        )
+    })?;
+    if is_archive(file) {
+        return Err(format!("{} is an archive rather than an executable", file).into());
+    }
+    let contents = read_release_file(settings, release, file, on_progress)?;
+    verify(settings, release, file, &contents)?;
+
+    let current_exe = exe_path()?;
+    if let Some(previous_exe) = previous_exe_path() {
+        fs::copy(&current_exe, previous_exe)?;
+    }
+    let downloaded_exe = with_added_extension(&current_exe, "download");
+    fs::write(&downloaded_exe, contents)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&downloaded_exe, fs::Permissions::from_mode(0o755))?;
+    }
+    let replaced = replace_exe(&downloaded_exe);
+    let _ = fs::remove_file(downloaded_exe);
+    replaced
+}
+
+/// Puts back the executable from before the last update.
+pub fn roll_back() -> Result<(), Box<dyn Error>> {
+    let previous_exe = match previous_exe_path() {
+        Some(previous_exe) if previous_exe.exists() => previous_exe,
+        _ => return Err("there's no previous version to roll back to".into()),
+    };
+    replace_exe(&previous_exe)?;
+    fs::remove_file(previous_exe)?;
+    Ok(())
+}
+
+/// Removes the executables updates and rollbacks moved aside. The one running can't be removed
+/// on Windows and is left for the next start.
+pub fn clean_up() {
+    let exe = match exe_path() {
+        Ok(exe) => exe,
+        Err(_) => return,
+    };
+    let (directory, exe_name) = match (exe.parent(), exe.file_name()) {
+        (Some(directory), Some(exe_name)) => (directory, exe_name.to_string_lossy()),
+        _ => return,
+    };
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        if name_before_moved_aside(&file_name.to_string_lossy()) == Some(&exe_name) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Checks for and installs updates on a background thread so the window keeps responding.
+#[derive(Default)]
+pub struct Updater {
+    pub status: Arc<Mutex<UpdateStatus>>,
+    /// Whether the update window is showing. A check Dorothy starts on its own only opens it
+    /// when there's an update, so being offline doesn't pop up an error every start.
+    pub open: bool,
+    /// Whether the window has been opened for the release found by the last check, so closing
+    /// it with "Not Now" keeps it closed.
+    announced: bool,
+}
+
+impl Updater {
+    pub fn status(&self) -> UpdateStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Whether to show the update window, opening it once when a check finds an update.
+    pub fn should_show(&mut self) -> bool {
+        if !self.announced && matches!(self.status(), UpdateStatus::Available(_)) {
+            self.announced = true;
+            self.open = true;
+        }
+        self.open
+    }
+
+    fn set_status(&self, status: UpdateStatus) {
+        *self.status.lock().unwrap() = status;
+    }
+
+    fn run(&self, ctx: &egui::Context, task: impl FnOnce() -> UpdateStatus + Send + 'static) {
+        let status = self.status.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let result = task();
+            *status.lock().unwrap() = result;
+            ctx.request_repaint();
+        });
+    }
+
+    pub fn check(&mut self, ctx: &egui::Context, settings: &UpdateSettings, opened_by_user: bool) {
+        if matches!(
+            self.status(),
+            UpdateStatus::Checking | UpdateStatus::Downloading(_)
+        ) {
+            return;
+        }
+        self.open |= opened_by_user;
+        self.announced = false;
+        self.set_status(UpdateStatus::Checking);
+        let settings = settings.clone();
+        self.run(ctx, move || match latest_release(&settings) {
+            Ok(Some(release)) => UpdateStatus::Available(release),
+            Ok(None) => UpdateStatus::UpToDate,
+            Err(e) => UpdateStatus::Failed(format!("Couldn't check for updates: {}", e)),
+        });
+    }
+
+    pub fn install(
+        &mut self,
+        ctx: &egui::Context,
+        settings: &UpdateSettings,
+        release: ReleaseInfo,
+    ) {
+        self.set_status(UpdateStatus::Downloading(DownloadProgress {
+            version: release.version.clone(),
+            downloaded: 0,
+            total: None,
+        }));
+        let settings = settings.clone();
+        let status = self.status.clone();
+        let progress_ctx = ctx.clone();
+        self.run(ctx, move || {
+            let mut on_progress = |downloaded, total| {
+                *status.lock().unwrap() = UpdateStatus::Downloading(DownloadProgress {
+                    version: release.version.clone(),
+                    downloaded,
+                    total,
+                });
+                progress_ctx.request_repaint();
+            };
+            match install(&settings, &release, &mut on_progress) {
+                Ok(()) => UpdateStatus::Installed(release.version),
+                Err(e) => {
+                    UpdateStatus::Failed(format!("Couldn't update to {}: {}", release.version, e))
+                }
+            }
+        });
+    }
+
+    pub fn roll_back(&mut self) {
+        self.open = true;
+        self.set_status(match roll_back() {
+            Ok(()) => UpdateStatus::RolledBack,
+            Err(e) => UpdateStatus::Failed(format!("Couldn't roll back: {}", e)),
+        });
+    }
+}
+
+pub fn place_updater(updater: &mut Updater, settings: &UpdateSettings, ui: &mut Ui) {
+    match updater.status() {
+        UpdateStatus::Idle => {
+            if ui.button("Check for Updates").clicked() {
+                updater.check(ui.ctx(), settings, true);
+            }
+        }
+        UpdateStatus::Checking => {
+            ui.horizontal(|ui| {
+                ui.add(egui::Spinner::new());
+                ui.label("Checking for updates...");
+            });
+        }
+        UpdateStatus::UpToDate => {
+            ui.label(format!(
+                "Dorothy {} is the latest version.",
+                cargo_crate_version!()
+            ));
+            if ui.button("Check Again").clicked() {
+                updater.check(ui.ctx(), settings, true);
+            }
+        }
+        UpdateStatus::Available(release) => {
+            ui.heading(format!("Dorothy {} is available", release.version));
+            ui.label(format!("You have {}.", cargo_crate_version!()));
+            if !release.name.is_empty() {
+                ui.add_space(5.);
+                ui.strong(&release.name);
+            }
+            ui.add_space(5.);
+            egui::ScrollArea::vertical()
+                .max_height(250.)
+                .show(ui, |ui| {
+                    if release.notes.trim().is_empty() {
+                        ui.label("This release doesn't have any notes.");
+                    } else {
+                        ui.label(&release.notes);
+                    }
+                });
+            ui.add_space(5.);
+            ui.horizontal(|ui| {
+                if ui.button("Update").clicked() {
+                    updater.install(ui.ctx(), settings, release.clone());
+                }
+                if ui.button("Not Now").clicked() {
+                    updater.open = false;
+                }
+            });
+        }
+        UpdateStatus::Downloading(progress) => {
+            ui.label(format!("Downloading Dorothy {}...", progress.version));
+            match progress.fraction() {
+                Some(fraction) => {
+                    ui.add(egui::ProgressBar::new(fraction).show_percentage());
+                }
+                None => {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Spinner::new());
+                        ui.label(format!("{:.1} MB", progress.downloaded as f32 / 1_000_000.));
+                    });
+                }
+            }
+        }
+        UpdateStatus::Installed(version) => {
+            ui.heading("Please Restart!");
+            ui.label(format!(
+                "Dorothy has updated to {}. Restart Dorothy to use it.",
+                version
+            ));
+            if can_roll_back() && ui.button("Roll Back").clicked() {
+                updater.roll_back();
+            }
+        }
+        UpdateStatus::RolledBack => {
+            ui.heading("Please Restart!");
+            ui.label("Dorothy has rolled back to the version from before the update. Restart Dorothy to use it.");
+        }
+        UpdateStatus::Failed(error) => {
+            ui.label(error);
+            if ui.button("Try Again").clicked() {
+                updater.check(ui.ctx(), settings, true);
+            }
+        }
+    }
+}
+
+/// Dorothy's version and whether there's a newer one, in the About Dorothy window.
+pub fn place_about(updater: &mut Updater, settings: &UpdateSettings, ui: &mut Ui) {
+    ui.heading(format!("Dorothy {}", cargo_crate_version!()));
+    if settings.source == UpdateSource::GitHub {
+        ui.hyperlink_to(
+            format!("{}/{}", settings.repo_owner, settings.repo_name),
+            format!(
+                "https://github.com/{}/{}",
+                settings.repo_owner, settings.repo_name
+            ),
+        );
+    }
+    ui.separator();
+    place_updater(updater, settings, ui);
+}
+
+/// Where to look for updates, in the Settings menu.
+pub fn place_update_settings(settings: &mut UpdateSettings, ui: &mut Ui) {
+    ui.horizontal(|ui| {
+        ui.label("Source");
+        ui.radio_value(&mut settings.source, UpdateSource::GitHub, "GitHub");
+        ui.radio_value(&mut settings.source, UpdateSource::Mirror, "Mirror");
+    });
+    match settings.source {
+        UpdateSource::GitHub => {
+            egui::Grid::new("update_source_grid").show(ui, |ui| {
+                ui.label("Owner");
+                ui.text_edit_singleline(&mut settings.repo_owner);
+                ui.end_row();
+                ui.label("Repository");
+                ui.text_edit_singleline(&mut settings.repo_name);
+                ui.end_row();
+                ui.label("Executable");
+                ui.text_edit_singleline(&mut settings.bin_name);
+                ui.end_row();
+            });
+        }
+        UpdateSource::Mirror => {
+            ui.horizontal(|ui| {
+                ui.label("Mirror");
+                ui.text_edit_singleline(&mut settings.mirror)
+                    .on_hover_text(format!(
+                        "A directory or http(s) URL with a {} listing its releases",
+                        MIRROR_INDEX
+                    ));
+            });
+        }
+    }
+    ui.horizontal(|ui| {
+        ui.label("Channel");
+        ui.radio_value(&mut settings.channel, UpdateChannel::Stable, "Stable");
+        ui.radio_value(
+            &mut settings.channel,
+            UpdateChannel::PreRelease,
+            "Pre-release",
+        )
+        .on_hover_text("Also update to pre-releases, like 0.1.0-beta.1");
+    });
+    ui.horizontal(|ui| {
+        ui.label("Public Key");
+        ui.text_edit_singleline(&mut settings.public_key)
+            .on_hover_text("Minisign public key updates have to be signed with. Leave empty to only check checksums.");
+    });
+    if ui.button("Reset to Default").clicked() {
+        *settings = UpdateSettings::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_asset_skips_archives() {
+        let assets = [
+            "dorothy_egui_bin-x86_64-pc-windows-msvc.zip",
+            "dorothy_egui_bin-x86_64-pc-windows-msvc.exe",
+            "dorothy_egui_bin-x86_64-pc-windows-msvc.exe.minisig",
+            "dorothy_egui_bin-x86_64-unknown-linux-musl.tar.gz",
+            "SHA256SUMS",
+        ]
+        .map(String::from);
+        let asset = |target| release_asset(&assets, "dorothy_egui_bin", target);
+        assert_eq!(
+            asset("x86_64-pc-windows-msvc").as_deref(),
+            Some("dorothy_egui_bin-x86_64-pc-windows-msvc.exe")
+        );
+        let assets = [
+            "dorothy_egui_bin-x86_64-unknown-linux-musl.tar.gz",
+            "dorothy_egui_bin-x86_64-unknown-linux-musl.tar.xz",
+        ]
+        .map(String::from);
+        assert_eq!(
+            release_asset(&assets, "dorothy_egui_bin", "x86_64-unknown-linux-musl"),
+            None
+        );
+    }
+
+    #[test]
+    fn release_asset_falls_back_to_the_same_os_and_architecture() {
+        let assets = [
+            "dorothy_egui_bin-x86_64-pc-windows-gnu.exe",
+            "dorothy_egui_bin-x86_64-unknown-linux-musl",
+        ]
+        .map(String::from);
+        let asset = |target| release_asset(&assets, "dorothy_egui_bin", target);
+        assert_eq!(
+            asset("x86_64-pc-windows-msvc").as_deref(),
+            Some("dorothy_egui_bin-x86_64-pc-windows-gnu.exe")
+        );
+        assert_eq!(
+            asset("x86_64-unknown-linux-gnu").as_deref(),
+            Some("dorothy_egui_bin-x86_64-unknown-linux-musl")
+        );
+        assert_eq!(asset("aarch64-apple-darwin"), None);
+
+        let assets = ["dorothy_egui_bin.exe".to_string()];
+        assert_eq!(
+            release_asset(&assets, "dorothy_egui_bin", "x86_64-pc-windows-msvc").as_deref(),
+            Some("dorothy_egui_bin.exe")
+        );
+    }
+
+    #[test]
+    fn executables_moved_aside_are_recognised() {
+        let name = name_before_moved_aside;
+        assert_eq!(
+            name("dorothy_egui_bin.exe.old"),
+            Some("dorothy_egui_bin.exe")
+        );
+        assert_eq!(
+            name("dorothy_egui_bin.exe.old.12"),
+            Some("dorothy_egui_bin.exe")
+        );
+        assert_eq!(name("dorothy_egui_bin.old.1"), Some("dorothy_egui_bin"));
+        assert_eq!(name("dorothy_egui_bin.exe"), None);
+        assert_eq!(name("dorothy_egui_bin.exe.old.x"), None);
+        assert_eq!(name("dorothy_egui_bin.exe.old."), None);
+        assert_eq!(name("dorothy_egui_bin.exe.older"), None);
+    }
+}